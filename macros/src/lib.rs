@@ -0,0 +1,379 @@
+//! Procedural macros for [kat](https://docs.rs/kat).
+//!
+//! This crate is an implementation detail of kat, its macros
+//! are re-exported from there and should be used through `kat::`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, Ident, ItemFn, LitStr, Token};
+
+/// Generates one `#[test]` function per test case of a data file.
+///
+/// See the kat crate documentation for usage.
+#[proc_macro_attribute]
+pub fn suite(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(attr as LitStr);
+    let runner = parse_macro_input!(item as ItemFn);
+
+    match expand_suite(&path, runner) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// private. should not be used directly
+///
+/// Includes an embedded data file, configured with a string literal.
+/// A path without extension gets the extension of its format.
+#[proc_macro]
+pub fn __include_data(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as IncludeData);
+
+    match expand_include_data(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// private. should not be used directly
+///
+/// Lists the keys of the fields, which are marked with `#[inherit]`,
+/// like serde deserializes them, including their `rename` and `rename_all`.
+#[proc_macro_derive(__DeriveInherited, attributes(inherit))]
+pub fn __derive_inherited(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_inherited(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// The path of an embedded data file, along with its format
+struct IncludeData {
+    path: LitStr,
+    format: Option<Ident>,
+}
+
+impl Parse for IncludeData {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let format = if input.parse::<Option<Token![,]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(IncludeData { path, format })
+    }
+}
+
+fn expand_include_data(input: &IncludeData) -> syn::Result<TokenStream2> {
+    let mut relative = PathBuf::from(input.path.value());
+    if relative.extension().is_none() {
+        let extension = match input.format.as_ref().map(|format| format.to_string()).as_deref() {
+            None | Some("Toml") => "toml",
+            Some("Cavp") => "rsp",
+            Some("Json") | Some("Wycheproof") => "json",
+            Some("Yaml") => "yaml",
+            Some("Ron") => "ron",
+            Some(_) => return Err(syn::Error::new(input.format.span(), "Unknown data file format")),
+        };
+        relative.set_extension(extension);
+    }
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+        syn::Error::new(
+            input.path.span(),
+            "Cargo manifest directory environment variable is undefinded",
+        )
+    })?;
+    let filepath = PathBuf::from(manifest_dir).join(relative).display().to_string();
+
+    Ok(quote! { include_str!(#filepath) })
+}
+
+fn expand_suite(path: &LitStr, mut runner: ItemFn) -> syn::Result<TokenStream2> {
+    let mut relative = PathBuf::from(path.value());
+    if relative.extension().is_none() {
+        relative.set_extension("toml");
+    }
+
+    if relative.extension().is_none_or(|extension| extension != "toml") {
+        return Err(syn::Error::new(
+            path.span(),
+            "#[kat::suite] only supports toml files",
+        ));
+    }
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+        syn::Error::new(
+            path.span(),
+            "Cargo manifest directory environment variable is undefinded",
+        )
+    })?;
+    let filepath = PathBuf::from(manifest_dir).join(&relative);
+
+    if !filepath.is_file() {
+        return Err(syn::Error::new(
+            path.span(),
+            format!("{} is not a file, or wasn't found", filepath.display()),
+        ));
+    }
+
+    let content = std::fs::read_to_string(&filepath).map_err(|err| {
+        syn::Error::new(
+            path.span(),
+            format!(
+                "File {} was found, but could not be read: {}",
+                filepath.display(),
+                err
+            ),
+        )
+    })?;
+
+    let cases = cases(&content)
+        .map_err(|err| syn::Error::new(path.span(), format!("Unable to parse toml: {}", err)))?;
+
+    // Test attributes like #[ignore] apply to every generated case
+    let (case_attrs, fn_attrs): (Vec<Attribute>, Vec<Attribute>) = runner
+        .attrs
+        .drain(..)
+        .partition(|attr| attr.path().is_ident("ignore") || attr.path().is_ident("should_panic"));
+    runner.attrs = fn_attrs;
+
+    let runner_name = &runner.sig.ident;
+    let absolute = filepath.display().to_string();
+    let relative = relative.display().to_string();
+
+    let cases = cases.iter().enumerate().map(|(index, (name, skip))| {
+        // Cases skipped in the data file are ignored, unless the whole runner is
+        let skip = match skip {
+            Some(reason) if !case_attrs.iter().any(|attr| attr.path().is_ident("ignore")) => {
+                quote! { #[ignore = #reason] }
+            }
+            _ => quote! {},
+        };
+
+        quote! {
+            #[test]
+            #(#case_attrs)*
+            #skip
+            pub(super) fn #name() {
+                kat::__run_case(
+                    &SUITE,
+                    concat!(env!("CARGO_MANIFEST_DIR"), "/", #relative),
+                    #index,
+                    super::#runner_name,
+                    |test| kat::__debug_case!(test),
+                );
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[cfg(test)]
+        #runner
+
+        #[cfg(test)]
+        mod #runner_name {
+            use super::*;
+
+            // Rebuild the tests whenever the data file changes
+            const _: &[u8] = include_bytes!(#absolute);
+
+            // The data file is parsed once, and shared by every test case
+            static SUITE: kat::__SuiteCache = kat::__SuiteCache::new();
+
+            #(#cases)*
+        }
+    })
+}
+
+/// Derives a unique test function name for every test case in the file,
+/// along with the reason of its `skip` directive, if it has one
+fn cases(content: &str) -> Result<Vec<(Ident, Option<String>)>, toml::de::Error> {
+    let value: toml::Value = toml::from_str(content)?;
+    let cases = match value.get("test").and_then(|t| t.as_array()) {
+        Some(cases) => cases.as_slice(),
+        None => &[],
+    };
+
+    let mut taken = HashSet::new();
+    let names = cases
+        .iter()
+        .enumerate()
+        .map(|(index, case)| {
+            let name = case_name(case).unwrap_or_else(|| format!("case_{}", index));
+            let mut unique = name.clone();
+            let mut suffix = index;
+            // A suffixed name might be taken by another case as well
+            while !taken.insert(unique.clone()) {
+                unique = format!("{}_{}", name, suffix);
+                suffix += 1;
+            }
+            let name = unique;
+            (Ident::new(&name, Span::call_site()), skip_reason(case))
+        })
+        .collect();
+
+    Ok(names)
+}
+
+fn skip_reason(case: &toml::Value) -> Option<String> {
+    match case.get("skip")? {
+        toml::Value::Boolean(true) => Some("skipped in the data file".to_string()),
+        toml::Value::String(reason) => Some(reason.clone()),
+        _ => None,
+    }
+}
+
+fn case_name(case: &toml::Value) -> Option<String> {
+    if let Some(id) = case.get("id") {
+        let id = match id {
+            toml::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        return sanitize(&id).map(|id| format!("id_{}", id));
+    }
+
+    case.get("name").and_then(|n| n.as_str()).and_then(|name| {
+        // Names like "fn" or "128_bit" are no valid identifiers on their own
+        sanitize(name).map(|name| match syn::parse_str::<Ident>(&name) {
+            Ok(_) => name,
+            Err(_) => format!("case_{}", name),
+        })
+    })
+}
+
+/// Turns an arbitrary string into a snake case identifier fragment
+fn sanitize(s: &str) -> Option<String> {
+    let mut out = String::new();
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+
+    let out = out.trim_end_matches('_');
+    if out.is_empty() {
+        None
+    } else {
+        Some(out.to_string())
+    }
+}
+
+fn expand_inherited(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(input.ident.span(), "Only the fields of a struct can be inherited"));
+    };
+
+    let rename_all = serde_rename(&input.attrs, "rename_all")?;
+    let mut keys = Vec::new();
+    for field in data.fields.iter() {
+        let Some(inherit) = field.attrs.iter().find(|attr| attr.path().is_ident("inherit")) else {
+            continue;
+        };
+        inherit.meta.require_path_only()?;
+
+        let (Fields::Named(_), Some(ident)) = (&data.fields, &field.ident) else {
+            return Err(syn::Error::new(inherit.span(), "Only named fields can be inherited"));
+        };
+
+        let key = match serde_rename(&field.attrs, "rename")? {
+            Some(key) => key,
+            None => {
+                let name = ident.to_string();
+                let name = name.trim_start_matches("r#");
+                match &rename_all {
+                    Some(rule) => rename_field(name, rule).ok_or_else(|| {
+                        syn::Error::new(input.ident.span(), format!("Unknown rename_all rule \"{}\"", rule))
+                    })?,
+                    None => name.to_string(),
+                }
+            }
+        };
+        keys.push(key);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics kat::__Inherited for #name #ty_generics #where_clause {
+            const INHERITED: &'static [&'static str] = &[#(#keys),*];
+        }
+    })
+}
+
+/// Finds the value of `#[serde(rename = "..")]` or `#[serde(rename(deserialize = ".."))]`,
+/// or the same of another `option`, like `rename_all`
+fn serde_rename(attrs: &[Attribute], option: &str) -> syn::Result<Option<String>> {
+    let mut value = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident(option) {
+                return skip_meta(&meta);
+            }
+
+            if meta.input.peek(Token![=]) {
+                value = Some(meta.value()?.parse::<LitStr>()?.value());
+                return Ok(());
+            }
+
+            meta.parse_nested_meta(|inner| {
+                if inner.path.is_ident("deserialize") {
+                    value = Some(inner.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    skip_meta(&inner)
+                }
+            })
+        })?;
+    }
+    Ok(value)
+}
+
+/// Skips a serde option, which has nothing to do with the key of a field
+fn skip_meta(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        content.parse::<TokenStream2>()?;
+    }
+    Ok(())
+}
+
+/// Renames a snake case field like serde's `rename_all` does
+fn rename_field(name: &str, rule: &str) -> Option<String> {
+    let pascal = || {
+        name.split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+            })
+            .collect::<String>()
+    };
+
+    let renamed = match rule {
+        "lowercase" | "snake_case" => name.to_string(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_ascii_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let pascal = pascal();
+            let mut chars = pascal.chars();
+            chars.next().map(|first| first.to_ascii_lowercase().to_string() + chars.as_str()).unwrap_or_default()
+        }
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.to_ascii_uppercase().replace('_', "-"),
+        _ => return None,
+    };
+    Some(renamed)
+}
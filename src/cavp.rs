@@ -0,0 +1,367 @@
+//! NIST CAVP `.rsp` and `.req` files.
+//!
+//! A file consists of sections, each starting with one or more
+//! bracketed parameter lines, followed by blocks of `KEY = value` lines,
+//! separated by blank lines. Every bracketed parameter of a section
+//! becomes a global variable, every block a test case.
+//! ```text
+//! # CAVS 11.1
+//! [ENCRYPT]
+//! [Keylen = 128]
+//!
+//! COUNT = 0
+//! KEY = 00000000000000000000000000000000
+//! PLAINTEXT = f34481ec3cc627bacd5dc3fb08f273e6
+//! CIPHERTEXT = 0336763e966d92595a567cc9ce537f5e
+//! ```
+
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::error::ParseError;
+use crate::outcome::Expectation;
+use crate::format::{CaseInfo, Directives, Section};
+use crate::__DeserializeOwned;
+
+/// Key of the name of bare section headers, like `[ENCRYPT]`
+const SECTION_KEY: &str = "section";
+
+struct CavpSection<'a> {
+    /// Lines of the parameters
+    lines: Option<(usize, usize)>,
+    params: Vec<(String, &'a str)>,
+    cases: Vec<CavpCase<'a>>,
+}
+
+struct CavpCase<'a> {
+    lines: (usize, usize),
+    fields: Vec<(String, &'a str)>,
+}
+
+/// Deserializes every section of a CAVP file
+pub(crate) fn load<G, R, T>(content: &str) -> Result<Vec<Section<G, R, T>>, ParseError>
+where
+    G: __DeserializeOwned,
+    R: __DeserializeOwned,
+    T: __DeserializeOwned,
+{
+    parse(content)
+        .into_iter()
+        .map(|section| {
+            let global = G::deserialize(Fields(&section.params)).map_err(|err| ParseError {
+                message: format!("{} in the section parameters", err),
+                line: section.lines.map(|(start, _)| start),
+                field: err.key,
+                ..Default::default()
+            })?;
+
+            let tests = section
+                .cases
+                .iter()
+                .map(|case| {
+                    T::deserialize(Fields(&case.fields)).map_err(|err| ParseError {
+                        message: format!("{} in the test case at line {}", err, case.lines.0),
+                        line: Some(case.lines.0),
+                        field: err.key,
+                        ..Default::default()
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+
+            Section::ungrouped(global, tests)
+        })
+        .collect()
+}
+
+/// Collects the count and lines of every test case
+pub(crate) fn case_infos(content: &str) -> Vec<CaseInfo> {
+    parse(content)
+        .into_iter()
+        .flat_map(|section| section.cases)
+        .map(|case| {
+            let field = |name: &str| {
+                case.fields
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| *value)
+            };
+
+            CaseInfo {
+                ident: field("count").map(|count| format!("count = {}", count)),
+                lines: Some(case.lines),
+                id: field("count").map(str::to_string),
+                name: None,
+                tags: Vec::new(),
+                expectation: Expectation {
+                    // Verification files state `Result = P` or `Result = F (reason)`
+                    valid: match field("valid").or(field("result")).map(str::to_ascii_lowercase) {
+                        Some(valid) if valid == "true" || valid.starts_with('p') => Some(true),
+                        Some(valid) if valid == "false" || valid.starts_with('f') => Some(false),
+                        _ => None,
+                    },
+                    expected_error: field("expected_error").map(str::to_string),
+                    acceptable: false,
+                },
+                directives: Directives {
+                    skip: field("skip").and_then(Directives::parse),
+                    should_panic: field("should_panic").and_then(Directives::parse),
+                    xfail: field("xfail").and_then(Directives::parse),
+                },
+            }
+        })
+        .collect()
+}
+
+fn parse(content: &str) -> Vec<CavpSection<'_>> {
+    let mut sections = vec![CavpSection {
+        lines: None,
+        params: Vec::new(),
+        cases: Vec::new(),
+    }];
+    let mut in_case = false;
+
+    for (number, line) in content.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+
+        if line.is_empty() {
+            in_case = false;
+            continue;
+        }
+
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let section = sections.last_mut().expect("there is always a section");
+
+        if line.starts_with('[') && line.ends_with(']') {
+            in_case = false;
+
+            let param = &line[1..line.len() - 1];
+            let param = match param.split_once('=') {
+                Some((key, value)) => (normalize(key), value.trim()),
+                None => (SECTION_KEY.to_string(), param.trim()),
+            };
+
+            // Parameters following test cases open a new section
+            if section.cases.is_empty() {
+                let start = section.lines.map_or(number, |(start, _)| start);
+                section.lines = Some((start, number));
+                set(&mut section.params, param);
+            } else {
+                sections.push(CavpSection {
+                    lines: Some((number, number)),
+                    params: vec![param],
+                    cases: Vec::new(),
+                });
+            }
+            continue;
+        }
+
+        // Bare words, like FAIL, act as flags
+        let field = match line.split_once('=') {
+            Some((key, value)) => (normalize(key), value.trim()),
+            None => (normalize(line), "true"),
+        };
+
+        if !in_case {
+            in_case = true;
+            section.cases.push(CavpCase {
+                lines: (number, number),
+                fields: Vec::new(),
+            });
+        }
+
+        let case = section.cases.last_mut().expect("a case was just opened");
+        case.lines.1 = number;
+        set(&mut case.fields, field);
+    }
+
+    sections.retain(|section| !section.cases.is_empty());
+    sections
+}
+
+fn set<'a>(fields: &mut Vec<(String, &'a str)>, (key, value): (String, &'a str)) {
+    match fields.iter_mut().find(|(k, _)| *k == key) {
+        Some(field) => field.1 = value,
+        None => fields.push((key, value)),
+    }
+}
+
+/// Turns keys like `Keylen` or `IV Len` into `keylen` and `iv_len`
+fn normalize(key: &str) -> String {
+    key.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect()
+}
+
+/// Error of the CAVP deserializer
+#[derive(Debug)]
+struct Error {
+    message: String,
+    /// Key of the field, the error occured in
+    key: Option<String>,
+}
+
+impl Error {
+    fn new(message: String) -> Self {
+        Error { message, key: None }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "{} for key `{}`", self.message, key),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<M: fmt::Display>(msg: M) -> Self {
+        Error::new(msg.to_string())
+    }
+}
+
+/// Deserializes a struct from the fields of a case or section
+struct Fields<'a, 'b>(&'b [(String, &'a str)]);
+
+impl<'de> de::Deserializer<'de> for Fields<'_, '_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(FieldsAccess {
+            fields: self.0.iter(),
+            key: None,
+            value: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct FieldsAccess<'a, 'b> {
+    fields: std::slice::Iter<'b, (String, &'a str)>,
+    key: Option<&'b str>,
+    value: Option<&'a str>,
+}
+
+impl<'de> MapAccess<'de> for FieldsAccess<'_, '_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        match self.fields.next() {
+            Some((key, value)) => {
+                self.key = Some(key);
+                self.value = Some(value);
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let key = self.key.take().unwrap_or_default();
+        let value = self.value.take().unwrap_or_default();
+
+        seed.deserialize(Text(value)).map_err(|err| Error {
+            key: err.key.or_else(|| Some(key.to_string())),
+            message: err.message,
+        })
+    }
+}
+
+/// Deserializes a value, that is only known as text.
+///
+/// Numbers and booleans are parsed from the text on demand.
+struct Text<'a>(&'a str);
+
+impl Text<'_> {
+    fn parse<T>(&self, expected: &str) -> Result<T, Error>
+    where
+        T: std::str::FromStr,
+        T::Err: fmt::Display,
+    {
+        self.0
+            .parse()
+            .map_err(|err| Error::new(format!("invalid {} `{}`: {}", expected, self.0, err)))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method: ident => $visit: ident: $ty: ty,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.parse::<$ty>(stringify!($ty))?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Text<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_str(self.0)
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.to_ascii_lowercase().as_str() {
+            "true" => visitor.visit_bool(true),
+            "false" => visitor.visit_bool(false),
+            _ => Err(Error::new(format!("invalid bool `{}`", self.0))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
@@ -2,6 +2,7 @@ pub use serde::{
     Deserialize as __Deserialize, 
     Deserializer as __Deserializer, 
     de::DeserializeOwned as __DeserializeOwned,
+    de::Error as __DeError,
 };
 
 /// private. should not be used directly
@@ -13,6 +14,9 @@ pub use __DeriveDeserialize as DeriveTable;
 /// Deserializable types
 pub mod types {
     use super::*;
+    use serde::de::Error;
+    use serde::{Serialize, Serializer};
+    use std::ops::Deref;
     /// Type to deserialize a toml String from
     pub type TomlString = String;
 
@@ -45,6 +49,226 @@ pub mod types {
         }
     }
 
+    /// Type to deserialize bytes from a hex encoded toml String.
+    ///
+    /// Digits may be upper or lower case, whitespace and
+    /// colons between them are ignored, e.g. `"0a:1B 2c"`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TomlHex {
+        pub bytes: Vec<u8>
+    }
+
+    impl<'de> __Deserialize<'de> for TomlHex {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: __Deserializer<'de>,
+        {
+            let s = TomlString::deserialize(deserializer)?;
+            let bytes = decode_hex(&s).map_err(D::Error::custom)?;
+            Ok(Self { bytes })
+        }
+    }
+
+    impl Serialize for TomlHex {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&encode_hex(&self.bytes))
+        }
+    }
+
+    impl From<Vec<u8>> for TomlHex {
+        fn from(bytes: Vec<u8>) -> Self {
+            Self { bytes }
+        }
+    }
+
+    impl Deref for TomlHex {
+        type Target = [u8];
+
+        fn deref(&self) -> &[u8] {
+            &self.bytes
+        }
+    }
+
+    /// Type to deserialize exactly `N` bytes from a hex encoded toml String.
+    /// 
+    /// Same as [TomlHex], but fails if the String doesn't decode to `N` bytes.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TomlHexArray<const N: usize> {
+        pub bytes: [u8; N]
+    }
+
+    impl<'de, const N: usize> __Deserialize<'de> for TomlHexArray<N> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: __Deserializer<'de>,
+        {
+            let hex = TomlHex::deserialize(deserializer)?;
+            let found = hex.bytes.len();
+            let bytes = hex.bytes.try_into().map_err(|_| {
+                D::Error::custom(format!("expected {} bytes, found {}", N, found))
+            })?;
+            Ok(Self { bytes })
+        }
+    }
+
+    impl<const N: usize> Serialize for TomlHexArray<N> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&encode_hex(&self.bytes))
+        }
+    }
+
+    impl<const N: usize> From<[u8; N]> for TomlHexArray<N> {
+        fn from(bytes: [u8; N]) -> Self {
+            Self { bytes }
+        }
+    }
+
+    impl<const N: usize> Deref for TomlHexArray<N> {
+        type Target = [u8; N];
+
+        fn deref(&self) -> &[u8; N] {
+            &self.bytes
+        }
+    }
+
+    /// Type to deserialize bytes from a base64 encoded toml String.
+    ///
+    /// Uses the standard alphabet, padding is optional and
+    /// whitespace is ignored.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TomlBase64 {
+        pub bytes: Vec<u8>
+    }
+
+    impl<'de> __Deserialize<'de> for TomlBase64 {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: __Deserializer<'de>,
+        {
+            let s = TomlString::deserialize(deserializer)?;
+            let bytes = decode_base64(&s).map_err(D::Error::custom)?;
+            Ok(Self { bytes })
+        }
+    }
+
+    impl Serialize for TomlBase64 {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&encode_base64(&self.bytes))
+        }
+    }
+
+    impl From<Vec<u8>> for TomlBase64 {
+        fn from(bytes: Vec<u8>) -> Self {
+            Self { bytes }
+        }
+    }
+
+    impl Deref for TomlBase64 {
+        type Target = [u8];
+
+        fn deref(&self) -> &[u8] {
+            &self.bytes
+        }
+    }
+
+    fn decode_hex(s: &str) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::with_capacity(s.len() / 2);
+        let mut high = None;
+
+        for (offset, c) in s.chars().enumerate() {
+            if c.is_whitespace() || c == ':' {
+                continue;
+            }
+
+            let digit = c.to_digit(16).ok_or_else(|| {
+                format!("invalid hex character {:?} at offset {}", c, offset)
+            })? as u8;
+
+            match high.take() {
+                Some(high) => bytes.push(high << 4 | digit),
+                None => high = Some(digit),
+            }
+        }
+
+        match high {
+            Some(_) => Err("odd number of hex digits".to_string()),
+            None => Ok(bytes),
+        }
+    }
+
+    fn encode_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn decode_base64(s: &str) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+        let mut buffer = 0u32;
+        let mut bits = 0;
+        let mut digits = 0;
+        let mut padded = false;
+
+        for (offset, c) in s.chars().enumerate() {
+            if c.is_whitespace() {
+                continue;
+            }
+
+            if c == '=' {
+                padded = true;
+                continue;
+            }
+
+            let value = match c {
+                'A'..='Z' => c as u32 - 'A' as u32,
+                'a'..='z' => c as u32 - 'a' as u32 + 26,
+                '0'..='9' => c as u32 - '0' as u32 + 52,
+                '+' => 62,
+                '/' => 63,
+                _ => return Err(format!("invalid base64 character {:?} at offset {}", c, offset)),
+            };
+
+            if padded {
+                return Err(format!("invalid base64 character {:?} at offset {}, after padding", c, offset));
+            }
+
+            buffer = buffer << 6 | value;
+            bits += 6;
+            digits += 1;
+
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+            }
+        }
+
+        if digits % 4 == 1 {
+            return Err("invalid base64 length".to_string());
+        }
+
+        Ok(bytes)
+    }
+
+    fn encode_base64(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let buffer = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |buffer, (i, byte)| buffer | (*byte as u32) << (16 - 8 * i));
+
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    s.push(ALPHABET[(buffer >> (18 - 6 * i) & 0x3f) as usize] as char);
+                } else {
+                    s.push('=');
+                }
+            }
+        }
+
+        s
+    }
+
 }
 
 /// private. should not be used directly
@@ -69,6 +293,30 @@ macro_rules! __generate_impl_deserialize {
     };
 }
 
+/// private. should not be used directly
+#[macro_export]
+macro_rules! __generate_impl_try_deserialize {
+    ($var_name_from: ident, $from_ty: ty, $for_ty: ty, $error_ty: ty, $($body: tt)*) => {
+        impl TryFrom<$from_ty> for $for_ty {
+            type Error = $error_ty;
+
+            fn try_from($var_name_from: $from_ty) -> Result<Self, Self::Error> {
+                { $($body)* }
+            }
+        }
+
+        impl<'de> kat::__Deserialize<'de> for $for_ty {
+            fn deserialize<D>(deserializer: D) -> Result<$for_ty, D::Error>
+            where
+                D: kat::__Deserializer<'de>,
+            {
+                let f = <$from_ty>::deserialize(deserializer)?;
+                <$for_ty>::try_from(f).map_err(<D::Error as kat::__DeError>::custom)
+            }
+        }
+    };
+}
+
 /// Generate Deserialize trait for any type that is
 /// constructable from a type that implements Serde's Deserialize
 /// trait
@@ -135,3 +383,70 @@ macro_rules! impl_deserialize_from_toml_date {
         }
     ) => { kat::__generate_impl_deserialize!($var_name_from, kat::types::TomlDate, $for_ty, $($body)*); };
 }
+
+/// Generate Deserialize trait for any type that is
+/// fallibly constructable from a type that implements Serde's Deserialize
+/// trait. The error must implement Display
+#[macro_export]
+macro_rules! impl_deserialize_try_from_deserializable {
+    (
+        |$var_name_from: ident: $from_ty: ty| -> Result<$for_ty: ty, $error_ty: ty> {
+            $($body: tt)*
+        }
+    ) => { kat::__generate_impl_try_deserialize!($var_name_from, $from_ty, $for_ty, $error_ty, $($body)*); };
+}
+
+/// Generate Deserialize trait for any type that is
+/// fallibly constructable from a TomlString. The error must implement Display
+#[macro_export]
+macro_rules! impl_deserialize_try_from_toml_string {
+    (
+        |$var_name_from: ident| -> Result<$for_ty: ty, $error_ty: ty> {
+            $($body: tt)*
+        }
+    ) => { kat::__generate_impl_try_deserialize!($var_name_from, kat::types::TomlString, $for_ty, $error_ty, $($body)*); };
+}
+
+/// Generate Deserialize trait for any type that is
+/// fallibly constructable from a TomlInt. The error must implement Display
+#[macro_export]
+macro_rules! impl_deserialize_try_from_toml_int {
+    (
+        |$var_name_from: ident| -> Result<$for_ty: ty, $error_ty: ty> {
+            $($body: tt)*
+        }
+    ) => { kat::__generate_impl_try_deserialize!($var_name_from, kat::types::TomlInt, $for_ty, $error_ty, $($body)*); };
+}
+
+/// Generate Deserialize trait for any type that is
+/// fallibly constructable from a TomlFloat. The error must implement Display
+#[macro_export]
+macro_rules! impl_deserialize_try_from_toml_float {
+    (
+        |$var_name_from: ident| -> Result<$for_ty: ty, $error_ty: ty> {
+            $($body: tt)*
+        }
+    ) => { kat::__generate_impl_try_deserialize!($var_name_from, kat::types::TomlFloat, $for_ty, $error_ty, $($body)*); };
+}
+
+/// Generate Deserialize trait for any type that is
+/// fallibly constructable from a TomlBool. The error must implement Display
+#[macro_export]
+macro_rules! impl_deserialize_try_from_toml_bool {
+    (
+        |$var_name_from: ident| -> Result<$for_ty: ty, $error_ty: ty> {
+            $($body: tt)*
+        }
+    ) => { kat::__generate_impl_try_deserialize!($var_name_from, kat::types::TomlBool, $for_ty, $error_ty, $($body)*); };
+}
+
+/// Generate Deserialize trait for any type that is
+/// fallibly constructable from a TomlDate. The error must implement Display
+#[macro_export]
+macro_rules! impl_deserialize_try_from_toml_date {
+    (
+        |$var_name_from: ident| -> Result<$for_ty: ty, $error_ty: ty> {
+            $($body: tt)*
+        }
+    ) => { kat::__generate_impl_try_deserialize!($var_name_from, kat::types::TomlDate, $for_ty, $error_ty, $($body)*); };
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::Format;
+
+/// Error of loading a data file
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum KatError {
+    /// The data file doesn't exist, or isn't a file
+    NotFound {
+        path: PathBuf,
+    },
+
+    /// The data file was found, but could not be opened or read
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+
+    /// The content of the data file is malformed, or doesn't match
+    /// the variables it's deserialized into
+    Parse {
+        path: PathBuf,
+        format: Format,
+        /// The error message of the deserializer
+        message: String,
+        /// The line of the error, starting at 1, if the format reports it
+        line: Option<usize>,
+        /// The column of the error, starting at 1, if the format reports it
+        column: Option<usize>,
+        /// The path to the offending field, e.g. `test[2].key`
+        field: Option<String>,
+    },
+
+    /// The data file or configuration can't be used,
+    /// e.g. an unsupported file extension
+    Validation {
+        message: String,
+    },
+}
+
+impl KatError {
+    pub(crate) fn validation(message: impl Into<String>) -> Self {
+        KatError::Validation {
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn parse(path: PathBuf, format: Format, err: ParseError) -> Self {
+        KatError::Parse {
+            path,
+            format,
+            message: err.message,
+            line: err.line,
+            column: err.column,
+            field: err.field,
+        }
+    }
+}
+
+impl fmt::Display for KatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KatError::NotFound { path } => {
+                write!(f, "{} is not a file, or wasn't found", path.display())
+            }
+            KatError::Io { path, source } => write!(
+                f,
+                "File {} was found, but could not be read: {}",
+                path.display(),
+                source
+            ),
+            KatError::Parse {
+                path,
+                format,
+                message,
+                line,
+                column,
+                field,
+            } => {
+                write!(f, "Unable to parse {} file {}", format, path.display())?;
+                if let Some(line) = line {
+                    write!(f, ":{}", line)?;
+                }
+                if let Some(column) = column {
+                    write!(f, ":{}", column)?;
+                }
+                write!(f, ": {}", message)?;
+                if let Some(field) = field {
+                    write!(f, ", at {}", field)?;
+                }
+                Ok(())
+            }
+            KatError::Validation { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for KatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KatError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Error of deserializing the content of a data file,
+/// before it's known which file it belongs to
+#[derive(Debug, Default)]
+pub(crate) struct ParseError {
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub field: Option<String>,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        ParseError {
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the position, if the format reports one
+    pub fn at(mut self, position: Option<(usize, usize)>) -> Self {
+        if let Some((line, column)) = position {
+            self.line = Some(line);
+            self.column = Some(column);
+        }
+        self
+    }
+}
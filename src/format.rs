@@ -0,0 +1,732 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::value::UnitDeserializer;
+use serde::de::{DeserializeSeed, IgnoredAny};
+
+use crate::error::ParseError;
+use crate::kinds::{LayoutSeed, Untagged};
+use crate::outcome::Expectation;
+use crate::value::Value;
+use crate::{__DeError, __Deserialize, __DeserializeOwned, __Deserializer};
+
+/// Data file formats, kat can read test cases from.
+///
+/// The format is derived from the file extension, or configured
+/// explicitly in [kat_cfg](crate::kat_cfg). Formats behind a cargo
+/// feature only exist with that feature, so the enum is non-exhaustive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    /// Toml files, with a `[global]` table and `[[test]]` tables.
+    /// Extension `.toml`
+    Toml,
+
+    /// NIST CAVP response and request files. Extensions `.rsp` and `.req`
+    Cavp,
+
+    /// Json files, laid out like toml files. Extension `.json`.
+    /// Requires the `json` feature
+    #[cfg(feature = "json")]
+    Json,
+
+    /// Yaml files, laid out like toml files. Extensions `.yaml` and `.yml`.
+    /// Requires the `yaml` feature
+    #[cfg(feature = "yaml")]
+    Yaml,
+
+    /// Ron files, laid out like toml files. Extension `.ron`.
+    /// Requires the `ron` feature
+    #[cfg(feature = "ron")]
+    Ron,
+
+    /// Project Wycheproof json files, with test groups.
+    /// Has to be configured explicitly. Requires the `wycheproof` feature
+    #[cfg(feature = "wycheproof")]
+    Wycheproof,
+}
+
+impl Format {
+    /// Returns the format belonging to a file extension
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        match extension.to_ascii_lowercase().as_str() {
+            "toml" => Some(Format::Toml),
+            "rsp" | "req" => Some(Format::Cavp),
+            #[cfg(feature = "json")]
+            "json" => Some(Format::Json),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Some(Format::Yaml),
+            #[cfg(feature = "ron")]
+            "ron" => Some(Format::Ron),
+            _ => None,
+        }
+    }
+
+    /// The extension, which is assumed, if the configured path has none
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Toml => "toml",
+            Format::Cavp => "rsp",
+            #[cfg(feature = "json")]
+            Format::Json => "json",
+            #[cfg(feature = "yaml")]
+            Format::Yaml => "yaml",
+            #[cfg(feature = "ron")]
+            Format::Ron => "ron",
+            #[cfg(feature = "wycheproof")]
+            Format::Wycheproof => "json",
+        }
+    }
+
+    /// Whether files of the format can have test groups
+    pub(crate) fn has_groups(&self) -> bool {
+        !matches!(self, Format::Cavp)
+    }
+
+    /// Whether files of the format are laid out like toml files, with a `[global]`
+    /// table and arrays of test cases, which may be of several kinds
+    pub(crate) fn is_toml_like(&self) -> bool {
+        match self {
+            Format::Cavp => false,
+            #[cfg(feature = "wycheproof")]
+            Format::Wycheproof => false,
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Toml => write!(f, "toml"),
+            Format::Cavp => write!(f, "cavp"),
+            #[cfg(feature = "json")]
+            Format::Json => write!(f, "json"),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => write!(f, "yaml"),
+            #[cfg(feature = "ron")]
+            Format::Ron => write!(f, "ron"),
+            #[cfg(feature = "wycheproof")]
+            Format::Wycheproof => write!(f, "wycheproof"),
+        }
+    }
+}
+
+/// How the test cases of a suite are laid out in its data files
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct CaseLayout {
+    /// Whether the test cases are nested in test groups
+    pub grouped: bool,
+    /// Names of the case tables, if there are several kinds of test cases
+    pub kinds: Option<&'static [&'static str]>,
+    /// Fields of the test cases, which are inherited from the global variables,
+    /// by their key. The fields of every kind, in order of `kinds`
+    pub inherited: &'static [&'static [&'static str]],
+}
+
+/// private. should not be used directly
+///
+/// Keys of the fields of a test case, which are marked with `#[inherit]`.
+pub trait __Inherited {
+    const INHERITED: &'static [&'static str];
+}
+
+/// Test cases, which share the same global variables
+pub(crate) struct Section<G, R, T> {
+    pub global: G,
+    pub groups: Vec<Group<R, T>>,
+}
+
+/// Test cases, which share the same group variables
+pub(crate) struct Group<R, T> {
+    pub group: R,
+    pub tests: Vec<T>,
+}
+
+impl<G, R, T> Section<G, R, T> {
+    /// Creates a section for formats without test groups
+    pub fn ungrouped(global: G, tests: Vec<T>) -> Result<Self, ParseError>
+    where
+        R: __DeserializeOwned,
+    {
+        let group = R::deserialize(UnitDeserializer::<serde::de::value::Error>::new())
+            .map_err(|err| ParseError::new(err.to_string()))?;
+
+        Ok(Section {
+            global,
+            groups: vec![Group { group, tests }],
+        })
+    }
+
+    /// Iterates over every test case, along with its global and group variables
+    pub fn cases(sections: &[Self]) -> impl Iterator<Item = (&G, &R, &T)> {
+        sections.iter().flat_map(|section| {
+            section.groups.iter().flat_map(move |group| {
+                group
+                    .tests
+                    .iter()
+                    .map(move |test| (&section.global, &group.group, test))
+            })
+        })
+    }
+}
+
+/// Identifies a test case inside its data file
+#[derive(Default, Clone)]
+pub(crate) struct CaseInfo {
+    pub ident: Option<String>,
+    pub lines: Option<(usize, usize)>,
+    pub directives: Directives,
+    /// The id of the case, like its `id`, CAVP `COUNT` or Wycheproof `tcId`
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub expectation: Expectation,
+}
+
+/// Reserved case keys, which tell the runner how to treat a test case.
+///
+/// A directive set to `true` is stored as an empty string.
+#[derive(Default, Clone)]
+pub(crate) struct Directives {
+    /// The case isn't run, with the reason
+    pub skip: Option<String>,
+    /// The case has to panic, with a substring of the panic message
+    pub should_panic: Option<String>,
+    /// The case is expected to fail, with the reason
+    pub xfail: Option<String>,
+}
+
+impl Directives {
+    /// Reads a directive from the text of a value, `true` and `false` are flags
+    pub fn parse(value: &str) -> Option<String> {
+        match value.to_ascii_lowercase().as_str() {
+            "true" => Some(String::new()),
+            "false" => None,
+            _ => Some(value.to_string()),
+        }
+    }
+}
+
+/// Deserializes the sections of a data file.
+///
+/// Test groups are only read, if the layout is grouped, otherwise every
+/// section consists of a single group. Test cases are read from the
+/// `[[test]]` tables, or the tables named after its kinds, if there are any.
+/// Formats, which don't support the layout, have to be ruled out beforehand.
+pub(crate) fn load<G, R, T>(
+    format: Format,
+    content: &str,
+    layout: &CaseLayout,
+) -> Result<Vec<Section<G, R, T>>, ParseError>
+where
+    G: __DeserializeOwned,
+    R: __DeserializeOwned,
+    T: __DeserializeOwned,
+{
+    let filled = match layout.inherited.iter().all(|inherited| inherited.is_empty()) {
+        true => None,
+        false => Some(inherit(format, content, layout)?),
+    };
+
+    if let Some(kinds) = layout.kinds {
+        let (global, tests) = read(format, content, filled.as_ref(), LayoutSeed::new(kinds))?;
+        let tests = tests.into_file_order(kind_order(format, content, kinds).as_deref());
+        return Ok(vec![Section::ungrouped(global, tests)?]);
+    }
+
+    match format {
+        Format::Cavp => crate::cavp::load(content),
+        #[cfg(feature = "wycheproof")]
+        Format::Wycheproof => crate::wycheproof::load(content, layout.grouped),
+        _ if layout.grouped => {
+            let layout: GroupedLayout<G, R, T> = read(format, content, filled.as_ref(), PhantomData)?;
+            if !layout.tests.is_empty() {
+                return Err(ParseError::new(
+                    "Test cases have to be inside of a group, if the runner takes test groups",
+                ));
+            }
+
+            Ok(vec![Section {
+                global: layout.global,
+                groups: layout
+                    .groups
+                    .into_iter()
+                    .map(|group| Group {
+                        group: group.group,
+                        tests: group.tests,
+                    })
+                    .collect(),
+            }])
+        }
+        _ => {
+            let layout: Layout<G, T> = read(format, content, filled.as_ref(), PhantomData)?;
+            Ok(vec![Section::ungrouped(layout.global, layout.tests)?])
+        }
+    }
+}
+
+/// Reads the content of a file, that is laid out like a toml file, and fills in
+/// the inherited fields, which a test case omits. They're taken from the
+/// `[defaults]` table, or otherwise from the `[global]` table.
+fn inherit(format: Format, content: &str, layout: &CaseLayout) -> Result<Value, ParseError> {
+    let mut file: Value = from_str(format, content)?;
+
+    // The inherited fields of every kind, along with their value
+    let defaults: Vec<Vec<(&str, Value)>> = layout
+        .inherited
+        .iter()
+        .map(|inherited| {
+            inherited
+                .iter()
+                .filter_map(|&field| {
+                    ["defaults", "global"]
+                        .iter()
+                        .find_map(|table| file.get(table)?.get(field))
+                        .map(|value| (field, value.clone()))
+                })
+                .collect()
+        })
+        .collect();
+
+    let Value::Map(root) = &mut file else {
+        return Ok(file);
+    };
+
+    let tables = layout.kinds.unwrap_or(&["test"]);
+    for (key, value) in root.iter_mut() {
+        let Value::Seq(values) = value else {
+            continue;
+        };
+
+        let key = key.as_str().unwrap_or_default();
+        if let Some(kind) = tables.iter().position(|table| *table == key) {
+            fill(values, &defaults[kind]);
+        } else if key == "group" && layout.kinds.is_none() {
+            for group in values.iter_mut() {
+                let Value::Map(entries) = group else {
+                    continue;
+                };
+                if let Some((_, Value::Seq(tests))) = entries.iter_mut().find(|(key, _)| key.as_str() == Some("test")) {
+                    fill(tests, &defaults[0]);
+                }
+            }
+        }
+    }
+
+    Ok(file)
+}
+
+/// Adds the `defaults` to every test case, which doesn't define them itself.
+/// A field, which a test case sets to null, isn't inherited.
+fn fill(cases: &mut [Value], defaults: &[(&str, Value)]) {
+    for case in cases {
+        let Value::Map(entries) = case else {
+            continue;
+        };
+
+        for (field, value) in defaults {
+            if !entries.iter().any(|(key, _)| key.as_str() == Some(*field)) {
+                entries.push((Value::String(field.to_string()), value.clone()));
+            }
+        }
+    }
+}
+
+/// Deserializes the content of a file, that is laid out like a toml file,
+/// or the file with its inherited fields filled in, if there is one.
+/// Errors of the filled in file have no position.
+fn read<'de, S: DeserializeSeed<'de>>(
+    format: Format,
+    content: &'de str,
+    filled: Option<&Value>,
+    seed: S,
+) -> Result<S::Value, ParseError> {
+    match filled {
+        Some(file) => tracked(file.clone(), seed, |_| None),
+        None => from_str_seed(format, content, seed),
+    }
+}
+
+/// Deserializes the content of a file, that is laid out like a toml file
+fn from_str<D: __DeserializeOwned>(format: Format, content: &str) -> Result<D, ParseError> {
+    from_str_seed(format, content, PhantomData)
+}
+
+/// Same as [from_str], but deserializes with a seed
+fn from_str_seed<'de, S: DeserializeSeed<'de>>(
+    format: Format,
+    content: &'de str,
+    seed: S,
+) -> Result<S::Value, ParseError> {
+    match format {
+        Format::Toml => {
+            let position = |err: &toml::de::Error| err.line_col().map(|(line, col)| (line + 1, col + 1));
+            tracked(&mut toml::Deserializer::new(content), seed, position)
+        }
+        #[cfg(feature = "json")]
+        Format::Json => json_from_str_seed(content, seed),
+        #[cfg(feature = "yaml")]
+        Format::Yaml => tracked(serde_yaml::Deserializer::from_str(content), seed, |err: &serde_yaml::Error| {
+            err.location().map(|location| (location.line(), location.column()))
+        }),
+        #[cfg(feature = "ron")]
+        Format::Ron => {
+            let position = |err: &ron::error::SpannedError| Some((err.position.line, err.position.col));
+            let spanned = |err: ron::error::SpannedError| ParseError::new(err.code.to_string()).at(position(&err));
+
+            let mut deserializer = ron::Deserializer::from_str(content).map_err(spanned)?;
+            let value = tracked(&mut deserializer, seed, |_| None).map_err(|err| {
+                // Errors of ron's deserializer lack the position,
+                // which is still known to the deserializer
+                let position = deserializer.span_error(ron::Error::Eof).position;
+                err.at(Some((position.line, position.col)))
+            })?;
+            deserializer
+                .end()
+                .map_err(|err| spanned(deserializer.span_error(err)))?;
+            Ok(value)
+        }
+        _ => Err(ParseError::new(format!("{} files are not laid out like toml files", format))),
+    }
+}
+
+/// Deserializes the content of a json file
+#[cfg(feature = "wycheproof")]
+pub(crate) fn json_from_str<D: __DeserializeOwned>(content: &str) -> Result<D, ParseError> {
+    json_from_str_seed(content, PhantomData)
+}
+
+#[cfg(any(feature = "json", feature = "wycheproof"))]
+fn json_from_str_seed<'de, S: DeserializeSeed<'de>>(content: &'de str, seed: S) -> Result<S::Value, ParseError> {
+    let position = |err: &serde_json::Error| match err.line() {
+        0 => None,
+        line => Some((line, err.column())),
+    };
+
+    let mut deserializer = serde_json::Deserializer::from_str(content);
+    let value = tracked(&mut deserializer, seed, position)?;
+    deserializer
+        .end()
+        .map_err(|err| ParseError::new(err.to_string()).at(position(&err)))?;
+    Ok(value)
+}
+
+/// Deserializes a value, keeping track of the path to the field, that failed
+fn tracked<'de, D, S, P>(deserializer: D, seed: S, position: P) -> Result<S::Value, ParseError>
+where
+    D: __Deserializer<'de>,
+    S: DeserializeSeed<'de>,
+    P: Fn(&D::Error) -> Option<(usize, usize)>,
+{
+    let mut track = serde_path_to_error::Track::new();
+    seed.deserialize(serde_path_to_error::Deserializer::new(deserializer, &mut track))
+        .map_err(|err| {
+            let field = track.path().to_string();
+
+            ParseError {
+                field: Some(field).filter(|field| field != "."),
+                ..ParseError::new(err.to_string()).at(position(&err))
+            }
+        })
+}
+
+/// Collects the info of every test case, in order of the deserialized cases.
+///
+/// Fails, if a reserved key of a test case, like `skip` or `tags`, has the wrong type.
+pub(crate) fn case_infos(
+    format: Format,
+    content: &str,
+    kinds: Option<&[&str]>,
+    count: usize,
+) -> Result<Vec<CaseInfo>, ParseError> {
+    let mut infos = match format {
+        Format::Cavp => crate::cavp::case_infos(content),
+        #[cfg(feature = "wycheproof")]
+        Format::Wycheproof => crate::wycheproof::case_infos(content)?,
+        _ => {
+            let lines = match format {
+                Format::Toml => toml_case_lines(content, kinds.unwrap_or(&["test", "group.test"]), count),
+                _ => None,
+            };
+
+            case_metas(format, content, kinds)?
+                .into_iter()
+                .enumerate()
+                .map(|(index, meta)| CaseInfo {
+                    ident: meta.ident(),
+                    lines: lines.as_ref().map(|lines| lines[index]),
+                    id: meta.id.as_ref().map(MetaValue::raw),
+                    name: meta.name.as_ref().map(MetaValue::raw),
+                    tags: match meta.tags {
+                        Some(Tags::One(tag)) => vec![tag],
+                        Some(Tags::Many(tags)) => tags,
+                        None => Vec::new(),
+                    },
+                    expectation: Expectation {
+                        valid: match meta.valid {
+                            Some(MetaValue::Bool(valid)) => Some(valid),
+                            _ => None,
+                        },
+                        expected_error: meta.expected_error.as_ref().map(MetaValue::raw),
+                        acceptable: matches!(meta.acceptable, Some(MetaValue::Bool(true))),
+                    },
+                    directives: Directives {
+                        skip: meta.skip.and_then(Directive::into_value),
+                        should_panic: meta.should_panic.and_then(Directive::into_value),
+                        xfail: meta.xfail.and_then(Directive::into_value),
+                    },
+                })
+                .collect()
+        }
+    };
+
+    infos.resize_with(count, CaseInfo::default);
+    Ok(infos)
+}
+
+/// Reads the fields of every test case, which identify it
+fn case_metas(format: Format, content: &str, kinds: Option<&[&str]>) -> Result<Vec<CaseMeta>, ParseError> {
+    match kinds {
+        // Cases are put in file order, like the deserialized ones
+        Some(kinds) => {
+            let seed = LayoutSeed::<IgnoredAny, Untagged<CaseMeta>>::new(kinds);
+            let (_, tests) = from_str_seed(format, content, seed)?;
+            let tests = tests.into_file_order(kind_order(format, content, kinds).as_deref());
+            Ok(tests.into_iter().map(|Untagged(meta)| meta).collect())
+        }
+        None => {
+            let layout: MetaLayout = from_str(format, content)?;
+            // Test cases outside of groups come first, files have either of them
+            let grouped = layout.groups.into_iter().flat_map(|group| group.tests);
+            Ok(layout.tests.into_iter().chain(grouped).collect())
+        }
+    }
+}
+
+#[derive(serde_derive::Deserialize)]
+#[serde(bound = "G: __DeserializeOwned, T: __DeserializeOwned")]
+struct Layout<G, T> {
+    global: G,
+
+    #[serde(rename = "test")]
+    tests: Vec<T>,
+}
+
+/// Layout of files with test groups, each `[[group]]` has its own `[[group.test]]` cases
+#[derive(serde_derive::Deserialize)]
+#[serde(bound = "G: __DeserializeOwned, R: __DeserializeOwned, T: __DeserializeOwned")]
+struct GroupedLayout<G, R, T> {
+    global: G,
+
+    #[serde(rename = "group")]
+    groups: Vec<GroupLayout<R, T>>,
+
+    /// Test cases outside of groups, which are ruled out
+    #[serde(rename = "test", default)]
+    tests: Vec<IgnoredAny>,
+}
+
+#[derive(serde_derive::Deserialize)]
+#[serde(bound = "R: __DeserializeOwned, T: __DeserializeOwned")]
+struct GroupLayout<R, T> {
+    #[serde(flatten)]
+    group: R,
+
+    #[serde(rename = "test")]
+    tests: Vec<T>,
+}
+
+#[derive(serde_derive::Deserialize)]
+struct MetaLayout {
+    #[serde(rename = "test", default)]
+    tests: Vec<CaseMeta>,
+
+    #[serde(rename = "group", default)]
+    groups: Vec<MetaGroup>,
+}
+
+#[derive(serde_derive::Deserialize)]
+struct MetaGroup {
+    #[serde(rename = "test", default)]
+    tests: Vec<CaseMeta>,
+}
+
+/// Fields which identify a test case, its directives and expected outcome
+#[derive(serde_derive::Deserialize)]
+struct CaseMeta {
+    #[serde(default, deserialize_with = "reserved")]
+    id: Option<MetaValue>,
+    #[serde(default, deserialize_with = "reserved")]
+    name: Option<MetaValue>,
+    #[serde(default, deserialize_with = "reserved")]
+    tags: Option<Tags>,
+    #[serde(default, deserialize_with = "reserved")]
+    valid: Option<MetaValue>,
+    #[serde(default, deserialize_with = "reserved")]
+    expected_error: Option<MetaValue>,
+    #[serde(default, deserialize_with = "reserved")]
+    acceptable: Option<MetaValue>,
+    #[serde(default, deserialize_with = "reserved")]
+    skip: Option<Directive>,
+    #[serde(default, deserialize_with = "reserved")]
+    should_panic: Option<Directive>,
+    #[serde(default, deserialize_with = "reserved")]
+    xfail: Option<Directive>,
+}
+
+/// Reads a reserved key, which is set like any other field of a test case,
+/// so ron files don't wrap it in `Some(..)`. A null value isn't set.
+fn reserved<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: __Deserializer<'de>,
+    T: __DeserializeOwned,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::Some(value) => T::deserialize(*value).map(Some).map_err(__DeError::custom),
+        value => T::deserialize(value).map(Some).map_err(__DeError::custom),
+    }
+}
+
+impl CaseMeta {
+    fn ident(&self) -> Option<String> {
+        match (&self.id, &self.name) {
+            (Some(id), _) => Some(format!("id = {}", id)),
+            (None, Some(name)) => Some(format!("name = {}", name)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// A scalar value of any format
+#[derive(serde_derive::Deserialize)]
+#[serde(untagged, expecting = "expected a number, bool or string")]
+enum MetaValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+}
+
+impl MetaValue {
+    /// The value, without quotes around strings
+    fn raw(&self) -> String {
+        match self {
+            MetaValue::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// Tags of a test case, either a single one or a list
+#[derive(serde_derive::Deserialize)]
+#[serde(untagged, expecting = "expected a tag, or a list of tags")]
+enum Tags {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// A directive is either a flag or a string
+#[derive(serde_derive::Deserialize)]
+#[serde(untagged, expecting = "expected a flag, or a string with the reason")]
+enum Directive {
+    Flag(bool),
+    Value(String),
+}
+
+impl Directive {
+    fn into_value(self) -> Option<String> {
+        match self {
+            Directive::Flag(true) => Some(String::new()),
+            Directive::Flag(false) => None,
+            Directive::Value(value) => Some(value),
+        }
+    }
+}
+
+impl fmt::Display for MetaValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetaValue::Int(i) => write!(f, "{}", i),
+            MetaValue::Float(float) => write!(f, "{}", float),
+            MetaValue::Bool(b) => write!(f, "{}", b),
+            MetaValue::String(s) => write!(f, "{:?}", s),
+        }
+    }
+}
+
+/// The kind of every test case in file order, for formats whose test cases
+/// of several kinds may alternate, like the `[[encrypt]]` and `[[decrypt]]`
+/// tables of a toml file. In other formats, the cases of a kind follow each other.
+fn kind_order(format: Format, content: &str, kinds: &[&str]) -> Option<Vec<usize>> {
+    match format {
+        Format::Toml => Some(toml_case_tables(content, kinds).into_iter().map(|(kind, _)| kind).collect()),
+        _ => None,
+    }
+}
+
+/// Finds the line range of every case table in a toml file, named after
+/// one of `tables`, like `test`, in file order.
+///
+/// Returns `None`, if the found tables don't match up with the
+/// deserialized test cases, e.g. for inline arrays of tables.
+fn toml_case_lines(content: &str, tables: &[&str], count: usize) -> Option<Vec<(usize, usize)>> {
+    let ranges: Vec<_> = toml_case_tables(content, tables)
+        .into_iter()
+        .map(|(_, range)| range)
+        .collect();
+
+    if ranges.len() == count {
+        Some(ranges)
+    } else {
+        None
+    }
+}
+
+/// Finds every case table in a toml file, named after one of `tables`,
+/// along with its line range. The index of its table name in `tables` comes first.
+fn toml_case_tables(content: &str, tables: &[&str]) -> Vec<(usize, (usize, usize))> {
+    let mut ranges = Vec::new();
+    // The table of the current case, along with its line range
+    let mut current: Option<(usize, (usize, usize))> = None;
+
+    for (number, line) in content.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+
+        if line.starts_with('[') {
+            let header = line.split('#').next().unwrap_or_default().trim();
+            let is_array = header.starts_with("[[");
+            let key: String = header
+                .trim_matches(|c| c == '[' || c == ']')
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect();
+
+            let table = tables.iter().position(|table| *table == key);
+            if let (true, Some(table)) = (is_array, table) {
+                ranges.extend(current.take());
+                current = Some((table, (number, number)));
+                continue;
+            }
+
+            // Subtables like `[test.nested]` belong to the case
+            let is_subtable = tables
+                .iter()
+                .any(|table| key.strip_prefix(table).is_some_and(|rest| rest.starts_with('.')));
+            if !is_subtable {
+                ranges.extend(current.take());
+            }
+        }
+
+        if let Some((_, (_, end))) = current.as_mut() {
+            if !line.is_empty() && !line.starts_with('#') {
+                *end = number;
+            }
+        }
+    }
+
+    ranges.extend(current);
+    ranges
+}
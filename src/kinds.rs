@@ -0,0 +1,305 @@
+//! Reads test cases of several kinds, each from its own array of tables.
+//!
+//! Suites with named kinds, like `[[encrypt]]` and `[[decrypt]]`, deserialize
+//! their test cases into an enum with a variant for every kind. A case is
+//! handed to the enum, as if it was tagged with the name of its kind, so
+//! `[[encrypt]]` tables become `__KatTest::encrypt` variants.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, DeserializeSeed, EnumAccess, IgnoredAny, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor};
+
+use crate::{__Deserialize, __Deserializer};
+
+/// Deserializes the global variables, along with the test cases of every kind.
+pub(crate) struct LayoutSeed<'k, G, T> {
+    kinds: &'k [&'k str],
+    marker: PhantomData<(G, T)>,
+}
+
+impl<'k, G, T> LayoutSeed<'k, G, T> {
+    pub fn new(kinds: &'k [&'k str]) -> Self {
+        LayoutSeed {
+            kinds,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, G, T> DeserializeSeed<'de> for LayoutSeed<'_, G, T>
+where
+    G: __Deserialize<'de>,
+    T: __Deserialize<'de>,
+{
+    type Value = (G, KindCases<T>);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: __Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("Layout", &["global"], self)
+    }
+}
+
+impl<'de, G, T> Visitor<'de> for LayoutSeed<'_, G, T>
+where
+    G: __Deserialize<'de>,
+    T: __Deserialize<'de>,
+{
+    type Value = (G, KindCases<T>);
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a global section and the test cases {:?}", self.kinds)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut global = None;
+        let mut tests: Vec<Vec<T>> = self.kinds.iter().map(|_| Vec::new()).collect();
+        let mut appearance = Vec::new();
+
+        while let Some(Key(key)) = map.next_key()? {
+            if key == "global" {
+                if global.is_some() {
+                    return Err(de::Error::duplicate_field("global"));
+                }
+                global = Some(map.next_value()?);
+            } else if let Some(kind) = self.kinds.iter().position(|kind| *kind == key) {
+                tests[kind] = map.next_value_seed(Cases {
+                    kind: self.kinds[kind],
+                    marker: PhantomData,
+                })?;
+                appearance.push(kind);
+            } else if key == "group" {
+                return Err(de::Error::custom("several kinds of test cases can't be inside of groups"));
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        let global = global.ok_or_else(|| de::Error::missing_field("global"))?;
+        Ok((global, KindCases { tests, appearance }))
+    }
+}
+
+/// The test cases of every kind, as they were read from the file
+pub(crate) struct KindCases<T> {
+    /// The cases of every kind, in order of `kinds`
+    tests: Vec<Vec<T>>,
+    /// The kinds, in order of their appearance in the file
+    appearance: Vec<usize>,
+}
+
+impl<T> KindCases<T> {
+    /// Puts the test cases in file order.
+    ///
+    /// `order` holds the kind of every case in file order, for formats like toml,
+    /// where the cases of several kinds may alternate. Without it, or if it doesn't
+    /// match up with the cases, the cases of every kind follow each other.
+    pub fn into_file_order(self, order: Option<&[usize]>) -> Vec<T> {
+        let order = order.filter(|order| {
+            self.tests
+                .iter()
+                .enumerate()
+                .all(|(kind, tests)| order.iter().filter(|&&case| case == kind).count() == tests.len())
+        });
+
+        let KindCases { mut tests, appearance } = self;
+        match order {
+            Some(order) => {
+                let mut tests: Vec<_> = tests.into_iter().map(Vec::into_iter).collect();
+                order.iter().filter_map(|&kind| tests[kind].next()).collect()
+            }
+            None => appearance
+                .iter()
+                .flat_map(|&kind| std::mem::take(&mut tests[kind]))
+                .collect(),
+        }
+    }
+}
+
+/// A key of the file, deserialized as an identifier, like the fields of a struct
+struct Key(String);
+
+impl<'de> __Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: __Deserializer<'de>,
+    {
+        struct KeyVisitor;
+
+        impl Visitor<'_> for KeyVisitor {
+            type Value = Key;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a key")
+            }
+
+            fn visit_str<E: de::Error>(self, key: &str) -> Result<Key, E> {
+                Ok(Key(key.to_string()))
+            }
+        }
+
+        deserializer.deserialize_identifier(KeyVisitor)
+    }
+}
+
+/// The test cases of a single kind
+struct Cases<'k, T> {
+    kind: &'k str,
+    marker: PhantomData<T>,
+}
+
+impl<'de, T: __Deserialize<'de>> DeserializeSeed<'de> for Cases<'_, T> {
+    type Value = Vec<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: __Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: __Deserialize<'de>> Visitor<'de> for Cases<'_, T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "an array of `{}` test cases", self.kind)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut tests = Vec::new();
+        while let Some(test) = seq.next_element_seed(Case {
+            kind: self.kind,
+            marker: PhantomData,
+        })? {
+            tests.push(test);
+        }
+        Ok(tests)
+    }
+}
+
+/// A test case, which is tagged with its kind
+struct Case<'k, T> {
+    kind: &'k str,
+    marker: PhantomData<T>,
+}
+
+impl<'de, T: __Deserialize<'de>> DeserializeSeed<'de> for Case<'_, T> {
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: __Deserializer<'de>,
+    {
+        T::deserialize(Tagged {
+            kind: self.kind,
+            case: deserializer,
+        })
+    }
+}
+
+/// Presents a test case as the enum variant of its kind
+struct Tagged<'k, D> {
+    kind: &'k str,
+    case: D,
+}
+
+impl<'de, D: __Deserializer<'de>> __Deserializer<'de> for Tagged<'_, D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, D: __Deserializer<'de>> EnumAccess<'de> for Tagged<'_, D> {
+    type Error = D::Error;
+    type Variant = Variant<D>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.kind.into_deserializer())?;
+        Ok((variant, Variant(self.case)))
+    }
+}
+
+/// The content of a tagged test case
+struct Variant<D>(D);
+
+impl<'de, D: __Deserializer<'de>> VariantAccess<'de> for Variant<D> {
+    type Error = D::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        IgnoredAny::deserialize(self.0).map(|_| ())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.0)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.deserialize_struct("", fields, visitor)
+    }
+}
+
+/// A test case of any kind, deserialized without its kind
+pub(crate) struct Untagged<T>(pub T);
+
+impl<'de, T: __Deserialize<'de>> __Deserialize<'de> for Untagged<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: __Deserializer<'de>,
+    {
+        struct UntaggedVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: __Deserialize<'de>> Visitor<'de> for UntaggedVisitor<T> {
+            type Value = Untagged<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a test case")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: EnumAccess<'de>,
+            {
+                let (_, case) = data.variant::<IgnoredAny>()?;
+                case.newtype_variant().map(Untagged)
+            }
+        }
+
+        deserializer.deserialize_enum("Untagged", &[], UntaggedVisitor(PhantomData))
+    }
+}
@@ -9,7 +9,7 @@
 //! 
 //! Under the hood, Kat uses [Serde](https://docs.rs/serde/latest/serde/index.html)
 //! and [Toml-rs](https://docs.rs/toml/latest/toml/) to deserialize test data.
-//! Both need to be added as dependencies to your crate.
+//! Serde needs to be added as a dependency to your crate.
 //!
 //! ## Getting Started
 //! ## Toml file layout
@@ -60,7 +60,7 @@
 //! ---
 //! The [kat_cfg] macro configures the filepath of your
 //! test file. The path will be interpreted, relative to the
-//! workspace root. The file extension can be ommited, since we only support toml.
+//! workspace root. The file extension can be ommited for toml files.
 //! String quotes around the path are not needed, since kat will
 //! directly interpolate the path from the macro expression.
 //! 
@@ -68,6 +68,36 @@
 //! // "WORKSPACE_ROOT/tests/data/my_data.toml"
 //! kat_cfg!(tests/data/my_data);
 //! ```
+//! The [Format] of the file is derived from its extension.
+//! For files with an unusual extension, the format can be
+//! configured explicitly.
+//! ```no_run
+//! // "WORKSPACE_ROOT/tests/data/AESGCM.rsp", a NIST CAVP file
+//! kat_cfg!(tests/data/AESGCM.rsp);
+//! 
+//! // "WORKSPACE_ROOT/tests/data/aes_gcm.txt"
+//! kat_cfg!(tests/data/aes_gcm.txt, format = Cavp);
+//! ```
+//! Test vectors split across many files can be run with a single runner,
+//! by configuring a directory or a glob. Every file is run against the same
+//! [global] and [test] layout, and failures name their file. Of a directory,
+//! every file with a known extension is read. Globs may use `*` and `?` in the
+//! file name. Paths, which aren't valid tokens, can be given as a string.
+//! ```no_run
+//! kat_cfg!(tests/data/aes);
+//! kat_cfg!(tests/data/aes/*.rsp);
+//! kat_cfg!("tests/data/aes/ecb_128_*.rsp");
+//! ```
+//! By default, the file is read, when the tests run. With `embed`, it's
+//! included into the test binary at compile time instead, so the binary
+//! doesn't depend on the source tree, e.g. when it's run on another machine,
+//! under qemu or Miri. The tests are rebuilt, whenever the file changes.
+//! ```no_run
+//! kat_cfg!(tests/data/my_data, embed);
+//! kat_cfg!(tests/data/aes_gcm.txt, format = Cavp, embed);
+//! ```
+//! Other data file formats, test groups and several kinds of test cases
+//! are described in the [Data Files section](./#data-files).
 //! Global and Test variables
 //! ---
 //! Now we define the layout for our global and test variables.
@@ -162,6 +192,295 @@
 //! an IO Error occured (e.g File open unsuccessful),
 //! or if toml parsing was erroneous.
 //! 
+//! A failing test case does not stop the runner. Every case is run,
+//! and afterwards the runner panics once, with a summary listing
+//! every failed case and its panic message.
+//! 
+//! Each failed case is reported with its position, its `id` or `name`
+//! field, if it has one, and the lines of its table in the data file.
+//! If the test struct implements Debug (see [Type Attributes](./#type-attributes)),
+//! the case itself is printed as well:
+//! ```text
+//! 2 of 3 test cases failed:
+//!   case 0 (id = 0, tests/data/my_data.toml:9-13): values don't match
+//!     test case: __KatTest { id: 0, input: "INPUT" }
+//!   case 2 (id = 2, tests/data/my_data.toml:23-27): values don't match
+//!     test case: __KatTest { id: 2, input: "INPUT" }
+//! ```
+//!
+//! ### Fallible Runners
+//! Instead of unwrapping every fallible call, the runner can return a
+//! `Result<(), E>`, with any error type `E`, which implements `Display`.
+//! Write the return type in place of the plain `->`:
+//! ```no_run
+//! run! {
+//!     |global, test| -> Result<(), MyError> {
+//!         let key = my_crate::Key::from_bytes(&test.key)?;
+//!         assert_eq!(key.encrypt(&test.plaintext)?, *test.ciphertext);
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//! A case, which returns an `Err`, fails just like a panicking one,
+//! and is listed in the summary with the error message:
+//! ```text
+//! 1 of 3 test cases failed:
+//!   case 1 (id = 2, tests/data/my_data.toml:14-18): returned Err: invalid key length
+//! ```
+//!
+//! ### Negative Test Cases
+//! Test cases, which an implementation has to reject, state so in the reserved
+//! keys `valid` and `expected_error`. Instead of checking them in every runner,
+//! pass the test case and the result of the implementation to [check_outcome]:
+//! ```no_run
+//! [[test]]
+//! id = 1
+//! tag = "ba8a7ba4f2bb4f5a"
+//!
+//! [[test]]
+//! id = 2
+//! tag = "0000"
+//! valid = false                   // has to return any error
+//!
+//! [[test]]
+//! id = 3
+//! tag = "ba8a"
+//! expected_error = "invalid tag"  // has to return this error
+//! ```
+//! ```no_run
+//! run! {
+//!     |global, test| -> {
+//!         kat::check_outcome(test, &my_crate::decrypt(&test.key, &test.tag));
+//!     }
+//! }
+//! ```
+//! Cases without these keys have to return `Ok`. Errors are compared by their
+//! `Display` output, [check_outcome_with] compares them by a kind of your choice:
+//! ```no_run
+//! kat::check_outcome_with(test, &result, |err: &DecryptError| format!("{:?}", err));
+//! ```
+//! With `acceptable = true`, a case may return `Ok` as well as an error.
+//! In NIST CAVP files, `Result = P` marks a valid and `Result = F` an invalid case.
+//! The `result` of a Wycheproof case is `valid`, `invalid` or `acceptable`.
+//!
+//! The expected outcome is the one of the test case, which runs on the current
+//! thread, so copies of a test case are checked as well. Threads, which a runner
+//! spawns itself, have to hand their result back to check it. With the [Suite]
+//! API, flatten an [Expectation] into the test variables and pass it to
+//! [check_outcome] instead.
+//!
+//! ### Case Directives
+//! Single test cases can be marked in the data file, with the reserved keys
+//! `skip`, `should_panic` and `xfail`. Each is either `true` or a string.
+//! ```no_run
+//! [[test]]
+//! id = 7
+//! skip = "takes an hour"          // not run at all
+//!
+//! [[test]]
+//! id = 8
+//! should_panic = "invalid tag"    // has to panic, with a message containing the string
+//!
+//! [[test]]
+//! id = 9
+//! xfail = "known bug, see #12"    // has to fail
+//! ```
+//! Skipped cases and expected failures are listed in the summary. A case marked
+//! with `xfail`, which passes, fails the run, so the marker is removed once the
+//! bug is fixed. With the [suite] attribute, skipped cases become `#[ignore]`d tests.
+//!
+//! ### Selecting Cases
+//! To run only some of the cases, set `KAT_FILTER` to a comma separated list
+//! of filters. A case is run, if it matches any of them.
+//! ```text
+//! KAT_FILTER=3..5 cargo test         // the cases at index 3 and 4
+//! KAT_FILTER=id=17 cargo test        // the case with `id = 17`
+//! KAT_FILTER=tag=slow,0 cargo test   // the cases tagged slow, and the first one
+//! ```
+//! Indices start at 0 and count every case of the file, ranges are written
+//! like in Rust: `3..5`, `3..=5`, `3..` or `..5`. Besides `id=`, there are
+//! `name=`, which matches the `name` key, and `tag=`, which matches the `tags`
+//! key of a case, either a string or a list of strings.
+//! ```no_run
+//! [[test]]
+//! id = 17
+//! tags = ["slow", "edge case"]
+//! ```
+//! For NIST CAVP files, the id is the `COUNT` of a case, and for Wycheproof files
+//! its `tcId`, while its `flags` are the tags. Filtered out cases are counted in the summary.
+//!
+//! Large suites can be split across several CI machines with `KAT_SHARD=i/n`,
+//! where `i` counts from 1 to `n`. Each shard runs every n-th case, so together,
+//! the shards run every case exactly once. `KAT_SAMPLE` runs a random subset,
+//! either a percentage or a number of cases:
+//! ```text
+//! KAT_SHARD=2/4 cargo test              // the second of four shards
+//! KAT_SAMPLE=10% cargo test             // a tenth of the cases
+//! KAT_SAMPLE=500 KAT_SEED=42 cargo test // the same 500 cases on every run
+//! ```
+//! The subset is chosen by `KAT_SEED`. Without one, a random seed is used, which
+//! is printed in the summary, or by a failing case of `#[kat::suite]`, so a failing
+//! sample can be run again. Shards and samples are taken of the filtered cases,
+//! and span every file of the suite.
+//!
+//! ### Parallel Cases
+//! By default, the cases run one after another. For expensive cases, write
+//! `parallel` in front of the runner, to run them across several threads:
+//! ```no_run
+//! run! {
+//!     parallel |global, test| -> {
+//!         assert_eq!(my_crate::keygen(&test.seed), *test.expected);
+//!     }
+//! }
+//! ```
+//! The global variables are shared by reference between the threads, so the
+//! variables have to be `Sync`. The runner uses as many threads as there are
+//! cores, `KAT_THREADS` sets a different number, e.g. `KAT_THREADS=1` to run the
+//! cases one after another again. The summary lists the failed cases in the order
+//! of the file, no matter which finished first.
+//!
+//! ### Setup and Teardown
+//! Work, which isn't specific to a single case, goes into hooks in front of the runner.
+//! `setup` runs once, before the first case. The value it returns is a fixture,
+//! which is passed by reference to the other hooks and the runner, after the test
+//! variables. `teardown` runs once, after the last case, and gets the fixture by value.
+//! ```no_run
+//! run! {
+//!     setup: || -> {
+//!         KeyCache::new()
+//!     },
+//!     before_each: |global, test, cache| -> {
+//!         cache.reset_counters();
+//!     },
+//!     after_each: |global, test, cache| -> {
+//!         cache.check_no_leaks();
+//!     },
+//!     teardown: |cache| -> {
+//!         cache.print_stats();
+//!     },
+//!     |global, test, cache| -> {
+//!         let key = cache.expand(&test.key);
+//!         assert_eq!(encrypt(&key, &test.plaintext), *test.ciphertext);
+//!     }
+//! }
+//! ```
+//! Every hook is optional, but they have to be in this order. Without `setup`,
+//! there's no fixture, so the parameter is left out everywhere. `before_each` and
+//! `after_each` take the same parameters as the runner, and `after_each` runs, even
+//! if the case failed. A panic in `before_each` or `after_each` fails the case.
+//! For `parallel` runners, the fixture has to be `Sync`.
+//!
+//! ### Report Files
+//! Since the [run] macro runs every case inside a single test, CI dashboards only
+//! see one test per suite. With `KAT_REPORT`, each suite writes a report file
+//! with every case, its id, outcome, duration and failure message:
+//! ```text
+//! KAT_REPORT=junit-dir:target/kat cargo test   // JUnit XML files
+//! KAT_REPORT=json-dir:target/kat cargo test    // json files
+//! ```
+//! The path is a directory, relative to the package, not a file. Each suite writes
+//! its own file, named after its module, e.g. `target/kat/my_tests.aes.xml`, so
+//! suites of several test binaries don't overwrite each other. Filtered out
+//! cases aren't listed, skipped cases are, and expected failures count as passed.
+//! The [suite] attribute writes no reports, since libtest already lists every case.
+//!
+//! With the [suite] attribute, every case is a test function on its own, so
+//! libtest's filter works as well, e.g. `cargo test my_suite::id_17`.
+//!
+//! ### Updating Expected Values
+//! Compare results with [expect] instead of `assert_eq!`. It takes a field
+//! of the test case and the actual value, which is converted into the field's type.
+//! ```no_run
+//! test! {
+//!     input: types::TomlHex,
+//!     expected: types::TomlHex
+//! }
+//!
+//! run! {
+//!     |global, test| -> {
+//!         let digest: Vec<u8> = my_crate::hash(&test.input);
+//!         kat::expect!(test.expected, digest);
+//!     }
+//! }
+//! ```
+//! When the output of an algorithm legitimately changes, run the tests
+//! with `KAT_UPDATE=1`:
+//! ```text
+//! KAT_UPDATE=1 cargo test
+//! ```
+//! Instead of comparing, every [expect] then records the actual value, and
+//! the runner writes the recorded values back into the toml file. Only the
+//! recorded keys of the `[[test]]` tables are touched, comments and formatting
+//! are kept. Missing keys are added.
+//!
+//! The key is the name of the field, serde renames are not taken into account.
+//! The field type has to implement `Serialize`, like all types in the [types] module.
+//!
+//! ### Several Suites in one File
+//! [kat_cfg], [global], [test] and [run] define items with fixed names, so each
+//! data file needs a module of its own. [kat_suite] defines all of them at once,
+//! inside a module named after the suite.
+//! ```no_run
+//! kat::kat_suite!(aes_ecb {
+//!     cfg: tests/data/aes_ecb.rsp,
+//!     global: { keylen: usize },
+//!     test: { key: types::TomlHex, plaintext: types::TomlHex },
+//!     run: |global, test| {
+//!         ...
+//!     }
+//! });
+//!
+//! kat::kat_suite!(aes_gcm {
+//!     cfg: tests/data/aes_gcm.json, format = Wycheproof,
+//!     global: { algorithm: String },
+//!     group: { #![serde(rename_all = "camelCase")] key_size: usize },
+//!     test: { #![serde(rename_all = "camelCase")] tc_id: usize },
+//!     run: #[ignore] |global, group, test| {
+//!         ...
+//!     }
+//! });
+//! ```
+//! The `cfg` takes the same arguments as [kat_cfg], the `group` is optional.
+//! [Hooks](./#setup-and-teardown) are written in front of `run`.
+//! The tests run as `aes_ecb::kat_tests::tests` and `aes_gcm::kat_tests::tests`.
+//!
+//! ### Loading Suites without Macros
+//! Outside of tests, e.g. in benchmarks, helpers or build scripts, a data file
+//! can be loaded with [Suite]. It resolves and parses the file just like [run] does,
+//! but returns a [KatError] instead of panicking.
+//! ```no_run
+//! #[derive(DeriveTable)]
+//! struct Global {
+//!     my_global_var: String,
+//! }
+//!
+//! #[derive(DeriveTable)]
+//! struct Test {
+//!     input: String,
+//!     expected: String,
+//! }
+//!
+//! let suite = kat::Suite::<Global, Test>::load("tests/data/my_data.toml")?;
+//! let global = suite.global();
+//! for test in suite.cases() {
+//!     ...
+//! }
+//! ```
+//! Relative paths are resolved against `CARGO_MANIFEST_DIR`, which cargo sets for
+//! tests, benchmarks and build scripts.
+//!
+//! A [KatError] tells apart a missing file, IO errors, parse errors and invalid
+//! configurations. Parse errors carry the line, column and path of the offending
+//! field, as far as the format reports them.
+//! ```no_run
+//! match kat::Suite::<Global, Test>::load("tests/data/my_data.toml") {
+//!     Ok(suite) => ...,
+//!     Err(KatError::NotFound { path }) => ...,
+//!     Err(KatError::Parse { line, field, .. }) => ...,
+//!     Err(err) => ...,
+//! }
+//! ```
+//! 
 //! ---
 //! All in all, we end up with a structure like this:
 //! ```no_run
@@ -215,6 +534,43 @@
 //!   }   
 //! }
 //! ```
+//! One test per case
+//! ---
+//! The [run] macro runs all test cases inside a single test function.
+//! If you'd like every case to show up, run, filter and fail on its own,
+//! annotate your runner function with the [suite] attribute instead.
+//! The data file is read at compile time, and a `#[test]` function is
+//! generated for every `[[test]]` entry. The path is relative to the
+//! workspace root and quoted this time.
+//! 
+//! The test functions are generated in a module, named like your runner
+//! function, and are named after the cases `id` or `name` field, or
+//! their position in the file, e.g. `my_suite::id_17` or `my_suite::case_3`.
+//! ```no_run
+//! #[derive(DeriveTable)]
+//! struct Global {
+//!     my_global_var: String
+//! }
+//! 
+//! #[derive(DeriveTable)]
+//! struct Test {
+//!     id: usize,
+//!     input: String,
+//!     expected: String,
+//! }
+//! 
+//! // Test attributes, like #[ignore] or #[should_panic],
+//! // are applied to every generated test function
+//! #[kat::suite("tests/data/my_data.toml")]
+//! fn my_suite(global: &Global, test: &Test) {
+//!     assert_eq!(test.input, test.expected);
+//! }
+//! ```
+//! Since the cases are counted at compile time, the tests
+//! are rebuilt, whenever the data file changes.
+//! The file is parsed once per test process and shared between
+//! its generated tests, so `Global` and `Test` must be `Send + Sync`.
+//! 
 //! Type Attributes
 //! ---
 //! Kat supports type attributes for both, types defined
@@ -228,6 +584,16 @@
 //!   my_attributed_type: usize
 //! }
 //! ```
+//! Attributes for the generated struct itself are written
+//! as inner attributes, at the top of the macro.
+//! ```no_run
+//! test! {
+//!   #![derive(Debug)]
+//!
+//!   id: usize,
+//!   input: String,
+//! }
+//! ```
 //! Deserializing Types
 //! ---
 //! ### Common Types
@@ -274,6 +640,28 @@
 //! 
 //! ...
 //! ```
+//! ### Byte Strings
+//! Keys, nonces and digests are typically stored as hex or base64
+//! encoded strings. The [types] module provides types, which decode
+//! them into bytes.
+//! ```no_run
+//! test! {
+//!     // Any amount of bytes
+//!     key: types::TomlHex,
+//!     // Exactly 12 bytes
+//!     nonce: types::TomlHexArray<12>,
+//!     digest: types::TomlBase64,
+//! }
+//! ```
+//! ```no_run
+//! [[test]]
+//! key = "000102030405060708090a0b0c0d0e0f"
+//! nonce = "00:01:02:03:04:05:06:07:08:09:0A:0B"
+//! digest = "AAECAw=="
+//! ```
+//! Hex strings can be upper or lower case, whitespace and colons are ignored.
+//! A string that can't be decoded fails to parse, naming the
+//! field and the offset of the bad character.
 //! 
 //! ### Deserializing Custom Types
 //! Since Kat internally deserializes its types with the help of Serde and Toml-rs,
@@ -398,7 +786,224 @@
 //! ```
 //! This is possible, since the macro generated 
 //! the code for the Deserialize trait for `MyArrayHolder`
+//!
+//! ### Fallible Conversions
+//! Not every value of a Toml type makes a valid custom type.
+//! For these cases, every `impl_deserialize_from_*` macro has a
+//! `impl_deserialize_try_from_*` counterpart, which generates a
+//! `TryFrom` implementation instead. The body returns a Result,
+//! the error type has to implement Display.
+//! ```no_run
+//! struct Length(usize);
+//!
+//! impl_deserialize_try_from_toml_int!(
+//!     |i| -> Result<Length, String> {
+//!         usize::try_from(i)
+//!             .map(Length)
+//!             .map_err(|_| format!("invalid length {}", i))
+//!     }
+//! );
+//!
+//! struct Checked(MyTable);
+//! impl_deserialize_try_from_deserializable!(
+//!     |table: MyTable| -> Result<Checked, &'static str> {
+//!         ...
+//!     }
+//! );
+//! ```
+//! An error fails parsing the data file, just like a value of the wrong type,
+//! so the message points to the offending key.
+//!
+//! Data Files
+//! ---
+//! Besides the toml layout of the tutorial, kat reads several other
+//! formats and layouts of data files.
+//! ### Json, Yaml and Ron files
+//! Besides toml, data files can be written in json, yaml or ron, by
+//! enabling the `json`, `yaml` or `ron` feature of kat. They're laid out
+//! just like toml files, with a `global` table and an array of `test` tables,
+//! and are deserialized into the same [global] and [test] structs.
+//! ```text
+//! {
+//!     "global": { "my_global_var": "This is a global variable" },
+//!     "test": [
+//!         { "id": 0, "input": "INPUT", "expected": "INPUT" },
+//!         { "id": 1, "input": "INPUT", "expected": "INPUT" }
+//!     ]
+//! }
+//! ```
+//! ### NIST CAVP files
+//! CAVP response (`.rsp`) and request (`.req`) files are read natively.
+//! Every bracketed parameter of a section, e.g. `[Keylen = 128]`, becomes
+//! a global variable, bare section names, e.g. `[ENCRYPT]`, are stored in the
+//! global variable `section`. Every block of `KEY = value` lines becomes a
+//! test case. Keys are lowercased, with any other character than letters
+//! and digits replaced by `_`, so `IVlen` becomes `ivlen`. Bare words inside a
+//! block, like `FAIL`, are read as `fail = true`.
+//! ```text
+//! [Keylen = 128]
+//! [IVlen = 96]
+//! 
+//! Count = 0
+//! Key = cf063a34d4a9a76c2c86787d3f96db71
+//! IV = 113b9785971864c83b01c787
+//! FAIL
+//! ```
+//! ```no_run
+//! global! {
+//!     keylen: usize,
+//!     ivlen: usize,
+//! }
 //! 
+//! test! {
+//!     count: usize,
+//!     key: String,
+//!     iv: String,
+//!     fail: Option<bool>,
+//! }
+//! ```
+//! Each section is run with its own global variables.
+//! ### Project Wycheproof files
+//! With the `wycheproof` feature enabled, [Wycheproof](wycheproof) json files
+//! can be read. Their test cases are nested in test groups, so besides the
+//! global and test variables, the group variables are defined with the [group]
+//! macro. The runner then receives the group of each test case, as its
+//! second argument.
+//! ```no_run
+//! use kat::wycheproof::TestResult;
+//! 
+//! kat_cfg!(tests/data/aes_gcm_test.json, format = Wycheproof);
+//! 
+//! global! {
+//!     algorithm: String,
+//! }
+//! 
+//! group! {
+//!     #![serde(rename_all = "camelCase")]
+//! 
+//!     key_size: usize,
+//!     tag_size: usize,
+//! }
+//! 
+//! test! {
+//!     #![serde(rename_all = "camelCase")]
+//! 
+//!     tc_id: usize,
+//!     key: String,
+//!     result: TestResult,
+//!     flags: Vec<String>,
+//! }
+//! 
+//! run! {
+//!     |global, group, test| -> {
+//!         let result = my_decrypt(&test.key);
+//! 
+//!         // Panics, if a valid input was rejected,
+//!         // or an invalid one accepted
+//!         test.result.check_result(&result);
+//!     }
+//! }
+//! ```
+//! ### Test Groups
+//! Toml, json, yaml and ron files can group their test cases as well, where
+//! each `[[group]]` table holds the group variables and its test cases.
+//! ```no_run
+//! [global]
+//! algorithm = "AES"
+//!
+//! [[group]]
+//! key_size = 128
+//!
+//! [[group.test]]
+//! id = 0
+//! key = "000102030405060708090a0b0c0d0e0f"
+//!
+//! [[group]]
+//! key_size = 256
+//!
+//! [[group.test]]
+//! id = 1
+//! key = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+//! ```
+//! Just like with Wycheproof files, the group variables are defined with
+//! the [group] macro, and the runner takes `|global, group, test|`. In json,
+//! the groups are an array `"group": [{ "key_size": 128, "test": [...] }]`.
+//! Once a runner takes groups, every test case has to be inside of a group.
+//! ### Several Kinds of Test Cases
+//! A file may hold several kinds of test cases, each in its own tables
+//! with its own fields, like `[[encrypt]]` and `[[decrypt]]`.
+//! ```no_run
+//! [global]
+//! key = "000102030405060708090a0b0c0d0e0f"
+//!
+//! [[encrypt]]
+//! plaintext = "00112233445566778899aabbccddeeff"
+//! ciphertext = "69c4e0d86a7b0430d8cdb78070b4c55a"
+//!
+//! [[decrypt]]
+//! ciphertext = "69c4e0d86a7b0430d8cdb78070b4c55a"
+//! plaintext = "00112233445566778899aabbccddeeff"
+//! ```
+//! Every kind is defined in [test] with the name of its tables, and the
+//! struct its cases are read into. The runner then has a body for every kind.
+//! ```no_run
+//! test! {
+//!     encrypt: Encrypt {
+//!         plaintext: TomlHex,
+//!         ciphertext: TomlHex,
+//!     },
+//!     decrypt: Decrypt {
+//!         ciphertext: TomlHex,
+//!         plaintext: TomlHex,
+//!     },
+//! }
+//!
+//! run! {
+//!     encrypt: |global, test| -> {
+//!         assert_eq!(my_encrypt(&global.key, &test.plaintext), *test.ciphertext);
+//!     },
+//!     decrypt: |global, test| -> {
+//!         assert_eq!(my_decrypt(&global.key, &test.ciphertext), *test.plaintext);
+//!     },
+//! }
+//! ```
+//! The cases are numbered in file order, whatever their kind, so `KAT_FILTER`
+//! indices count the cases of every kind. Tables, which aren't named like a
+//! Rust identifier, are mapped to a kind by `encrypt = "aes-encrypt": Encrypt { .. }`.
+//! Hooks receive the case as the generated `__KatTest` enum, with a variant
+//! for every kind. Several kinds can't be combined with test groups, a file
+//! with `[[group]]` tables fails to load, and they aren't supported by NIST
+//! CAVP and Wycheproof files.
+//! ### Inherited Fields
+//! Fields, which most test cases share, can be marked with `#[inherit]`,
+//! along with their other attributes. A test case, which omits such a field,
+//! takes it from the `[defaults]` table, or else from the `[global]` table.
+//! ```no_run
+//! [global]
+//! key_len = 256
+//!
+//! [[test]]
+//! plaintext = "00112233445566778899aabbccddeeff"
+//!
+//! [[test]]
+//! key_len = 128
+//! plaintext = "00112233445566778899aabbccddeeff"
+//! ```
+//! ```no_run
+//! test! {
+//!     #[inherit]
+//!     key_len: usize,
+//!     plaintext: TomlHex,
+//! }
+//! ```
+//! A field, which is neither set in the test case, nor in one of the tables,
+//! still fails to deserialize, while a field set to null isn't inherited.
+//! Fields are looked up by their key, so `#[serde(rename = "..")]` and
+//! `rename_all` apply. With several kinds of test cases, every kind inherits
+//! the fields, which are marked in its own struct. Inherited fields are
+//! supported by toml, json, yaml and ron files. Errors in such files are
+//! reported without their line.
+//!
 //! ## Final Notes
 //! It is discouraged to rename the crate, since many macros
 //! inside the crate use the `kat::` module namespace 
@@ -411,123 +1016,544 @@
 mod de;
 pub use de::*;
 
+mod cavp;
+mod format;
+pub use format::{Format, __Inherited};
+
+mod error;
+pub use error::KatError;
+
+mod suite;
+pub use suite::{NoGroup, Suite};
+
+mod runner;
+pub use runner::*;
+
+mod outcome;
+pub use outcome::{__current_expectation, check_outcome, check_outcome_with, Expectation, Expected};
+
+mod kinds;
+mod report;
+mod select;
+mod source;
+mod value;
+
+mod update;
+pub use update::__expect;
+
+#[cfg(feature = "wycheproof")]
+pub mod wycheproof;
+
+pub use kat_macros::{__DeriveInherited, __include_data, suite};
+
 /// Configure the test files location.
 #[macro_export]
 macro_rules! kat_cfg {
-    ($path1: tt$(/$path2: tt)*) => {
+    (@cfg [$($content: tt)*] [$($path: expr),*] [$($extension: expr)?] [$($format: ident)?]) => {
         #[allow(dead_code)]
-        const __FILEPATH_SLICE: &'static [&'static str] = &[
-            env!("CARGO_MANIFEST_DIR", "Cargo manifest directory environment variable is undefinded"),
-            stringify!($path1),
-            $(stringify!($path2),)*
-        ];
+        const __KAT_CFG: kat::__KatCfg = kat::__KatCfg {
+            module: module_path!(),
+            path: &[
+                env!("CARGO_MANIFEST_DIR", "Cargo manifest directory environment variable is undefinded"),
+                $($path,)*
+            ],
+            extension: kat::__optional!($($extension)?),
+            format: kat::__optional!($(kat::Format::$format)?),
+            content: kat::__optional!($($content)*),
+            kinds: __KAT_KINDS,
+            inherited: __KAT_INHERITED,
+        };
+    };
+    ($path: literal$(, format = $format: ident)?, embed) => {
+        kat::kat_cfg!(
+            @cfg [kat::__include_data!($path$(, $format)?)]
+            [$path] [] [$($format)?]
+        );
+    };
+    ($path: literal$(, format = $format: ident)?) => {
+        kat::kat_cfg!(@cfg [] [$path] [] [$($format)?]);
+    };
+    ($path1: tt$(/$path2: tt)*$(.$extension: ident)?$(, format = $format: ident)?, embed) => {
+        kat::kat_cfg!(
+            @cfg [include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR", "Cargo manifest directory environment variable is undefinded"),
+                "/", stringify!($path1),
+                $("/", stringify!($path2),)*
+                ".", kat::__extension!([$($extension)?] [$($format)?])
+            ))]
+            [stringify!($path1)$(, stringify!($path2))*]
+            [$(stringify!($extension))?]
+            [$($format)?]
+        );
+    };
+    ($path1: tt$(/$path2: tt)*$(.$extension: ident)?$(, format = $format: ident)?) => {
+        kat::kat_cfg!(
+            @cfg []
+            [stringify!($path1)$(, stringify!($path2))*]
+            [$(stringify!($extension))?]
+            [$($format)?]
+        );
+    };
+}
+
+/// private. should not be used directly
+///
+/// The extension of an embedded file, which is assumed
+/// from its format, if the path has none.
+#[macro_export]
+macro_rules! __extension {
+    ([$extension: ident] [$($format: ident)?]) => { stringify!($extension) };
+    ([] []) => { "toml" };
+    ([] [Toml]) => { "toml" };
+    ([] [Cavp]) => { "rsp" };
+    ([] [Json]) => { "json" };
+    ([] [Yaml]) => { "yaml" };
+    ([] [Ron]) => { "ron" };
+    ([] [Wycheproof]) => { "json" };
+}
+
+/// Defines a named suite, with its configuration, variables and runner.
+///
+/// Expands to a module of the given name, so several suites
+/// can live in the same file.
+#[macro_export]
+macro_rules! kat_suite {
+    (@cfg $name: ident [$($cfg: tt)*] , global: $($rest: tt)*) => {
+        kat::kat_suite!(@suite $name [$($cfg)*] global: $($rest)*);
+    };
+    (@cfg $name: ident [$($cfg: tt)*] $next: tt $($rest: tt)*) => {
+        kat::kat_suite!(@cfg $name [$($cfg)* $next] $($rest)*);
+    };
+    (
+        @suite $name: ident [$($cfg: tt)*]
+        global: { $($global: tt)* },
+        test: { $($test: tt)* },
+        $($run: tt)*
+    ) => {
+        mod $name {
+            use super::*;
+
+            kat::kat_cfg!($($cfg)*);
+            kat::global! { $($global)* }
+            kat::test! { $($test)* }
+            kat::run! { $($run)* }
+        }
+    };
+    (
+        @suite $name: ident [$($cfg: tt)*]
+        global: { $($global: tt)* },
+        group: { $($group: tt)* },
+        test: { $($test: tt)* },
+        $($run: tt)*
+    ) => {
+        mod $name {
+            use super::*;
+
+            kat::kat_cfg!($($cfg)*);
+            kat::global! { $($global)* }
+            kat::group! { $($group)* }
+            kat::test! { $($test)* }
+            kat::run! { $($run)* }
+        }
+    };
+    ($name: ident { cfg: $($rest: tt)* }) => {
+        kat::kat_suite!(@cfg $name [] $($rest)*);
     };
 }
 
+/// Compares a field of the test case with its actual value.
+///
+/// With `KAT_UPDATE=1`, the actual value is written back into the data file instead.
+#[macro_export]
+macro_rules! expect {
+    ($case: ident . $field: ident, $actual: expr $(,)?) => {
+        kat::__expect(&$case.$field, $actual, stringify!($field))
+    };
+}
+
+/// private. should not be used directly
+#[macro_export]
+macro_rules! __optional {
+    () => { None };
+    ($value: expr) => { Some($value) };
+}
+
 /// Defines the global variables inside the test file.
 #[macro_export]
 macro_rules! global {
-    ($($data: tt)*) => {
+    (@attrs [$($attrs: tt)*] #![$attr: meta] $($data: tt)*) => {
+        kat::global!(@attrs [$($attrs)* #[$attr]] $($data)*);
+    };
+    (@attrs [$($attrs: tt)*] $($data: tt)*) => {
 
         #[derive(kat::DeriveTable)]
+        $($attrs)*
         struct __KatGlobal {
             $($data)*
         }
     };
+    ($($data: tt)*) => { kat::global!(@attrs [] $($data)*); };
 }
 
 /// Defines the test specific variables inside the test file.
+///
+/// Fields marked with `#[inherit]` are taken from the `[defaults]` or `[global]`
+/// table, if a test case omits them. Several kinds of test cases are defined
+/// as `kind: Struct { .. }`, each read from the tables named after its kind,
+/// or as `kind = "table-name": Struct { .. }`, if the tables aren't named like a Rust identifier.
 #[macro_export]
 macro_rules! test {
-    ($($data: tt)*) => {
+    (@attrs [$($attrs: tt)*] #![$attr: meta] $($data: tt)*) => {
+        kat::test!(@attrs [$($attrs)* #[$attr]] $($data)*);
+    };
+    (@attrs [$($attrs: tt)*] $($data: tt)*) => {
 
-        #[derive(kat::DeriveTable)]
+        #[derive(kat::DeriveTable, kat::__DeriveInherited)]
+        $($attrs)*
         struct __KatTest {
             $($data)*
         }
+
+        kat::__expected!(__KatTest);
+
+        #[allow(dead_code)]
+        const __KAT_KINDS: Option<&[&str]> = None;
+
+        #[allow(dead_code)]
+        const __KAT_INHERITED: &[&[&str]] = &[<__KatTest as kat::__Inherited>::INHERITED];
+    };
+    (@kind [$($attrs: tt)*] $name: ident { #![$attr: meta] $($data: tt)* }) => {
+        kat::test!(@kind [$($attrs)* #[$attr]] $name { $($data)* });
     };
+    (@kind [$($attrs: tt)*] $name: ident { $($data: tt)* }) => {
+
+        #[derive(kat::DeriveTable, kat::__DeriveInherited)]
+        $($attrs)*
+        struct $name {
+            $($data)*
+        }
+
+        kat::__expected!($name);
+    };
+    // The name of the tables of a kind
+    (@section $kind: ident) => { stringify!($kind) };
+    (@section $kind: ident $section: literal) => { $section };
+    ($($kind: ident $(= $section: literal)? : $name: ident { $($data: tt)* }),+ $(,)?) => {
+        $(kat::test!(@kind [] $name { $($data)* });)+
+
+        #[derive(kat::DeriveTable)]
+        #[allow(non_camel_case_types)]
+        enum __KatTest {
+            $($(#[serde(rename = $section)])? $kind($name),)+
+        }
+
+        kat::__expected!(__KatTest);
+
+        #[allow(dead_code)]
+        const __KAT_KINDS: Option<&[&str]> = Some(&[$(kat::test!(@section $kind $($section)?)),+]);
+
+        #[allow(dead_code)]
+        const __KAT_INHERITED: &[&[&str]] = &[$(<$name as kat::__Inherited>::INHERITED),+];
+    };
+    ($($data: tt)*) => { kat::test!(@attrs [] $($data)*); };
+}
+
+/// private. should not be used directly
+///
+/// Looks up the expected outcome of the test case, which is running.
+#[macro_export]
+macro_rules! __expected {
+    ($name: ident) => {
+        impl kat::Expected for $name {
+            fn expectation(&self) -> kat::Expectation {
+                kat::__current_expectation()
+            }
+        }
+    };
+}
+
+/// Defines the group specific variables inside the test file.
+#[macro_export]
+macro_rules! group {
+    (@attrs [$($attrs: tt)*] #![$attr: meta] $($data: tt)*) => {
+        kat::group!(@attrs [$($attrs)* #[$attr]] $($data)*);
+    };
+    (@attrs [$($attrs: tt)*] $($data: tt)*) => {
+
+        #[derive(kat::DeriveTable)]
+        $($attrs)*
+        struct __KatGroup {
+            $($data)*
+        }
+    };
+    ($($data: tt)*) => { kat::group!(@attrs [] $($data)*); };
 }
 
 /// Runs the tests.
+///
+/// The test function `kat_tests::tests` is visible to the calling module,
+/// which may call it itself, e.g. with environment variables set.
+/// With `parallel` in front of the runner, the test cases are run across several threads.
+/// Suites with several kinds of test cases have a runner for every kind, like `encrypt: |global, test| -> { .. }`,
+/// named after the kind in [test]. Their runners don't take test groups.
+/// Runners may return a `Result<(), E>`, where `E: Display`.
+/// The optional `setup`, `before_each`, `after_each` and `teardown` hooks come first.
 #[macro_export]
 macro_rules! run {
-    (
-        $(#[$attr:meta])*
-        |$global_data: ident, $test_data: ident| -> {
-            $($body: tt)*
-        }
+    // Attributes of the test function
+    (@parse [$($attrs: tt)*] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        #[$attr: meta] $($rest: tt)*
+    ) => {
+        kat::run!(@parse [$($attrs)* #[$attr]] [$($setup)*] [$($before)*] [$($after)*] [$($teardown)*] $($rest)*);
+    };
+    (@parse [$($attrs: tt)*] [] [] [] []
+        setup: || $(->)? { $($body: tt)* }, $($rest: tt)*
+    ) => {
+        kat::run!(@parse [$($attrs)*] [{ $($body)* }] [] [] [] $($rest)*);
+    };
+    // Without setup, hooks and the runner get a unit fixture
+    (@parse [$($attrs: tt)*] [] [] [$($after: tt)*] [$($teardown: tt)*]
+        before_each: |$($arg: ident),*| $(->)? { $($body: tt)* }, $($rest: tt)*
+    ) => {
+        kat::run!(@parse [$($attrs)*] [] [[$($arg,)* _kat_no_fixture] { $($body)* }] [$($after)*] [$($teardown)*] $($rest)*);
+    };
+    (@parse [$($attrs: tt)*] [$($setup: tt)+] [] [$($after: tt)*] [$($teardown: tt)*]
+        before_each: |$($arg: ident),*| $(->)? { $($body: tt)* }, $($rest: tt)*
+    ) => {
+        kat::run!(@parse [$($attrs)*] [$($setup)+] [[$($arg),*] { $($body)* }] [$($after)*] [$($teardown)*] $($rest)*);
+    };
+    (@parse [$($attrs: tt)*] [] [$($before: tt)*] [] [$($teardown: tt)*]
+        after_each: |$($arg: ident),*| $(->)? { $($body: tt)* }, $($rest: tt)*
+    ) => {
+        kat::run!(@parse [$($attrs)*] [] [$($before)*] [[$($arg,)* _kat_no_fixture] { $($body)* }] [$($teardown)*] $($rest)*);
+    };
+    (@parse [$($attrs: tt)*] [$($setup: tt)+] [$($before: tt)*] [] [$($teardown: tt)*]
+        after_each: |$($arg: ident),*| $(->)? { $($body: tt)* }, $($rest: tt)*
+    ) => {
+        kat::run!(@parse [$($attrs)*] [$($setup)+] [$($before)*] [[$($arg),*] { $($body)* }] [$($teardown)*] $($rest)*);
+    };
+    (@parse [$($attrs: tt)*] [] [$($before: tt)*] [$($after: tt)*] []
+        teardown: || $(->)? { $($body: tt)* }, $($rest: tt)*
+    ) => {
+        kat::run!(@parse [$($attrs)*] [] [$($before)*] [$($after)*] [[_kat_no_fixture] { $($body)* }] $($rest)*);
+    };
+    (@parse [$($attrs: tt)*] [$($setup: tt)+] [$($before: tt)*] [$($after: tt)*] []
+        teardown: |$fixture: ident| $(->)? { $($body: tt)* }, $($rest: tt)*
+    ) => {
+        kat::run!(@parse [$($attrs)*] [$($setup)+] [$($before)*] [$($after)*] [[$fixture] { $($body)* }] $($rest)*);
+    };
+    // The runner, labeled with `run:` inside of kat_suite
+    (@parse [$($attrs: tt)*] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        run: $($rest: tt)*
+    ) => {
+        kat::run!(@parse [$($attrs)*] [$($setup)*] [$($before)*] [$($after)*] [$($teardown)*] $($rest)*);
+    };
+    (@parse [$($attrs: tt)*] [] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        $($mode: ident)? |$($arg: ident),*| $(->)? { $($body: tt)* } $(,)?
+    ) => {
+        kat::run!(@run [$($attrs)*] [$($mode)?] [()] [$($before)*] [$($after)*] [$($teardown)*]
+            [$($arg,)* _kat_no_fixture] { $($body)* });
+    };
+    (@parse [$($attrs: tt)*] [$($setup: tt)+] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        $($mode: ident)? |$($arg: ident),*| $(->)? { $($body: tt)* } $(,)?
+    ) => {
+        kat::run!(@run [$($attrs)*] [$($mode)?] [$($setup)+] [$($before)*] [$($after)*] [$($teardown)*]
+            [$($arg),*] { $($body)* });
+    };
+    // Runners, which return a Result
+    (@parse [$($attrs: tt)*] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        $($mode: ident)? |$($arg: ident),*| -> $result: ty { $($body: tt)* } $(,)?
+    ) => {
+        kat::run!(@parse [$($attrs)*] [$($setup)*] [$($before)*] [$($after)*] [$($teardown)*]
+            $($mode)? |$($arg),*| -> {
+                kat::__check_result((|| -> $result { $($body)* })());
+            });
+    };
+    // A runner for every kind of test case
+    (@parse [$($attrs: tt)*] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        parallel $kind: ident : $($rest: tt)*
+    ) => {
+        kat::run!(@kinds [$($attrs)*] [parallel] [$($setup)*] [$($before)*] [$($after)*] [$($teardown)*] []
+            $kind : $($rest)*);
+    };
+    (@parse [$($attrs: tt)*] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        $kind: ident : | $($rest: tt)*
+    ) => {
+        kat::run!(@kinds [$($attrs)*] [] [$($setup)*] [$($before)*] [$($after)*] [$($teardown)*] []
+            $kind : | $($rest)*);
+    };
+    (@parse $($rest: tt)*) => {
+        compile_error!(
+            "expected the hooks `setup`, `before_each`, `after_each` and `teardown`, in this order, followed by the runner `|global, test| -> { .. }`"
+        );
+    };
+    (@kinds [$($attrs: tt)*] [$($mode: ident)?] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        [$($kinds: tt)*] $kind: ident : |$($arg: ident),*| $(->)? { $($body: tt)* } $(, $($rest: tt)*)?
+    ) => {
+        kat::run!(@kinds [$($attrs)*] [$($mode)?] [$($setup)*] [$($before)*] [$($after)*] [$($teardown)*]
+            [$($kinds)* [$kind [$($arg),*] { $($body)* }]] $($($rest)*)?);
+    };
+    (@kinds [$($attrs: tt)*] [$($mode: ident)?] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        [$($kinds: tt)*] $kind: ident : |$($arg: ident),*| -> $result: ty { $($body: tt)* } $(, $($rest: tt)*)?
+    ) => {
+        kat::run!(@kinds [$($attrs)*] [$($mode)?] [$($setup)*] [$($before)*] [$($after)*] [$($teardown)*]
+            [$($kinds)* [$kind [$($arg),*] { kat::__check_result((|| -> $result { $($body)* })()); }]] $($($rest)*)?);
+    };
+    (@kinds [$($attrs: tt)*] [$($mode: ident)?] [] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        [$([$kind: ident [$($arg: ident),*] { $($body: tt)* }])+]
+    ) => {
+        kat::run!(@run_kinds [$($attrs)*] [$($mode)?] [()] [$($before)*] [$($after)*] [$($teardown)*]
+            [$([$kind [$($arg,)* _kat_no_fixture] { $($body)* }])+]);
+    };
+    (@kinds [$($attrs: tt)*] [$($mode: ident)?] [$($setup: tt)+] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        [$([$kind: ident [$($arg: ident),*] { $($body: tt)* }])+]
+    ) => {
+        kat::run!(@run_kinds [$($attrs)*] [$($mode)?] [$($setup)+] [$($before)*] [$($after)*] [$($teardown)*]
+            [$([$kind [$($arg),*] { $($body)* }])+]);
+    };
+    (@run_kinds [$($attrs: tt)*] [$($mode: ident)?] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        [$([$kind: ident [$global_data: ident, $test_data: ident, $fixture: ident] { $($body: tt)* }])+]
     ) => {
         #[cfg(test)]
         mod kat_tests {
 
             use super::*;
 
-            #[derive(kat::DeriveTable)]
-            struct __KatFileLayout {
-                global: __KatGlobal,
-
-                #[serde(rename = "test")]
-                tests: Vec<__KatTest>
+            #[test]
+            $($attrs)*
+            pub(super) fn tests() {
+                let __kat_fixture = $($setup)*;
+                let __kat_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    kat::__runner!([$($mode)?] [])(
+                        &__KAT_CFG,
+                        |test_case: &__KatTest| match test_case {
+                            $(__KatTest::$kind(test_case) => kat::__debug_case!(test_case),)+
+                        },
+                        |__kat_global: &__KatGlobal, __kat_test: &__KatTest| {
+                            let __kat_fixture = &__kat_fixture;
+                            kat::__case!([$($before)*] [$($after)*] (__kat_global, __kat_test, __kat_fixture) {
+                                match __kat_test {
+                                    $(__KatTest::$kind(__kat_case) => {
+                                        let ($global_data, $test_data, $fixture) = (__kat_global, __kat_case, __kat_fixture);
+                                        $($body)*
+                                    })+
+                                }
+                            });
+                        }
+                    );
+                }));
+                kat::__hook!([$($teardown)*] (__kat_fixture));
+                if let Err(payload) = __kat_result {
+                    std::panic::resume_unwind(payload);
+                }
             }
+        }
+    };
+    (@run [$($attrs: tt)*] [$($mode: ident)?] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        [$global_data: ident, $test_data: ident, $fixture: ident] { $($body: tt)* }
+    ) => {
+        #[cfg(test)]
+        mod kat_tests {
 
-            pub fn __read_file_as_string() -> Result<String, String> {
-                use std::path::PathBuf;
-                use std::fs::File;
-                use std::io::Read;
-
-                let mut filepath: PathBuf = __FILEPATH_SLICE.iter().collect();
-                filepath.set_extension("toml");
-
-                if !filepath.is_file() {
-                    return Err(format!(
-                        "{} is not a file, or wasn't found",
-                        filepath.display(),
-                    ))
-                }
+            use super::*;
 
-                let mut file = match File::open(&filepath) {
-                    Ok(file) => file,
-                    Err(err) => return Err(format!(
-                        "File {} was found, but could not be opened: {}",
-                        filepath.display(), err.to_string()
-                    ))
-                };
-
-                let mut content = String::new();
-
-                if let Err(err) = file.read_to_string(&mut content) {
-                    return Err(format!(
-                        "File {} was found, but could not be read: {}",
-                        filepath.display(), err.to_string()
-                    ))
+            #[test]
+            $($attrs)*
+            pub(super) fn tests() {
+                let __kat_fixture = $($setup)*;
+                let __kat_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    kat::__runner!([$($mode)?] [])(
+                        &__KAT_CFG,
+                        |test_case: &__KatTest| kat::__debug_case!(test_case),
+                        |$global_data: &__KatGlobal, $test_data: &__KatTest| {
+                            let $fixture = &__kat_fixture;
+                            kat::__case!([$($before)*] [$($after)*] ($global_data, $test_data, $fixture) { $($body)* });
+                        }
+                    );
+                }));
+                kat::__hook!([$($teardown)*] (__kat_fixture));
+                if let Err(payload) = __kat_result {
+                    std::panic::resume_unwind(payload);
                 }
-
-                Ok(content)
             }
+        }
+    };
+    (@run [$($attrs: tt)*] [$($mode: ident)?] [$($setup: tt)*] [$($before: tt)*] [$($after: tt)*] [$($teardown: tt)*]
+        [$global_data: ident, $group_data: ident, $test_data: ident, $fixture: ident] { $($body: tt)* }
+    ) => {
+        #[cfg(test)]
+        mod kat_tests {
+
+            use super::*;
 
             #[test]
-            $(#[$attr])*
-            fn tests() {
-
-                let file_content = match __read_file_as_string() {
-                    Ok(content) => content,
-                    Err(err) => { panic!("Error: {}", err) }
-                };
-
-                let ($global_data, kat_tests) = {
-                    let layout: __KatFileLayout = match toml::from_str(&file_content) {
-                        Ok(k) => k,
-                        Err(err) => { panic!("Unable to parse toml: {}", err.to_string()) }
-                    };
-
-                    (layout.global, layout.tests)
-                };
-
-                kat_tests.into_iter()
-                .for_each(|$test_data|{
-                    { $($body)* }
-                });
+            $($attrs)*
+            pub(super) fn tests() {
+                let __kat_fixture = $($setup)*;
+                let __kat_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    kat::__runner!([$($mode)?] [grouped])(
+                        &__KAT_CFG,
+                        |test_case: &__KatTest| kat::__debug_case!(test_case),
+                        |$global_data: &__KatGlobal, $group_data: &__KatGroup, $test_data: &__KatTest| {
+                            let $fixture = &__kat_fixture;
+                            kat::__case!(
+                                [$($before)*] [$($after)*]
+                                ($global_data, $group_data, $test_data, $fixture) { $($body)* }
+                            );
+                        }
+                    );
+                }));
+                kat::__hook!([$($teardown)*] (__kat_fixture));
+                if let Err(payload) = __kat_result {
+                    std::panic::resume_unwind(payload);
+                }
             }
         }
     };
+    ($($input: tt)*) => {
+        kat::run!(@parse [] [] [] [] [] $($input)*);
+    };
+}
+
+/// private. should not be used directly
+///
+/// Runs a hook of [run], if there is one, with the given arguments.
+#[macro_export]
+macro_rules! __hook {
+    ([] ($($value: expr),*)) => {};
+    ([[$($arg: ident),*] { $($body: tt)* }] ($($value: expr),*)) => {{
+        #[allow(unused_variables)]
+        let ($($arg,)*) = ($($value,)*);
+        $($body)*
+    }};
+}
+
+/// private. should not be used directly
+///
+/// Runs the body of a test case between its `before_each` and `after_each` hooks.
+/// `after_each` also runs, if the body panicked.
+#[macro_export]
+macro_rules! __case {
+    ([] [] ($($value: expr),*) { $($body: tt)* }) => {{
+        $($body)*
+    }};
+    ([$($before: tt)*] [$($after: tt)*] ($($value: expr),*) { $($body: tt)* }) => {{
+        kat::__hook!([$($before)*] ($($value),*));
+        let __kat_case = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| { $($body)* }));
+        kat::__hook!([$($after)*] ($($value),*));
+        if let Err(payload) = __kat_case {
+            std::panic::resume_unwind(payload);
+        }
+    }};
+}
+
+/// private. should not be used directly
+///
+/// The function, which runs the test cases in the given mode.
+#[macro_export]
+macro_rules! __runner {
+    ([] []) => { kat::__run_cases };
+    ([] [grouped]) => { kat::__run_grouped_cases };
+    ([parallel] []) => { kat::__run_parallel_cases };
+    ([parallel] [grouped]) => { kat::__run_parallel_grouped_cases };
 }
\ No newline at end of file
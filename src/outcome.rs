@@ -0,0 +1,166 @@
+//! Checks the result of an implementation against the expected outcome of a test case.
+//!
+//! Negative test cases state their outcome in the reserved keys `valid` and
+//! `expected_error`. The runner reads them for every case, so the variables
+//! of [test](crate::test) look up the outcome of the case, which is running.
+
+use std::cell::RefCell;
+use std::fmt::Display;
+
+thread_local! {
+    /// The expected outcome of the test case, which runs on this thread
+    static CURRENT: RefCell<Option<Expectation>> = const { RefCell::new(None) };
+}
+
+/// Sets the expectation of the test case, which starts running on this thread
+pub(crate) fn begin_case(expectation: &Expectation) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(expectation.clone()));
+}
+
+pub(crate) fn end_case() {
+    CURRENT.with(|current| *current.borrow_mut() = None);
+}
+
+/// private. should not be used directly
+///
+/// The expected outcome of the test case, which runs on this thread.
+#[track_caller]
+pub fn __current_expectation() -> Expectation {
+    match CURRENT.with(|current| current.borrow().clone()) {
+        Some(expectation) => expectation,
+        None => panic!("check_outcome can only check a test case on the thread, which runs it"),
+    }
+}
+
+/// A test case, which states its expected outcome.
+///
+/// Implemented by the variables of [test](crate::test), and by [Expectation]
+/// itself, e.g. to check an [Expectation], which is flattened into the
+/// test variables of a [Suite](crate::Suite).
+pub trait Expected {
+    /// The expected outcome of the test case
+    fn expectation(&self) -> Expectation;
+}
+
+impl Expected for Expectation {
+    fn expectation(&self) -> Expectation {
+        self.clone()
+    }
+}
+
+impl<C: Expected + ?Sized> Expected for &C {
+    fn expectation(&self) -> Expectation {
+        (**self).expectation()
+    }
+}
+
+/// The expected outcome of a test case.
+///
+/// Without `valid` and `expected_error`, the case has to succeed.
+/// With `valid = false`, it has to fail with any error, and with
+/// `expected_error`, it has to fail with that error.
+/// ```no_run
+/// [[test]]
+/// id = 3
+/// tag = "0000"
+/// expected_error = "InvalidTag"
+/// ```
+/// Deserializes from the reserved keys, so it can be flattened into
+/// the test variables, when loading a [Suite](crate::Suite) without the runner.
+#[derive(serde_derive::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expectation {
+    /// Whether the case has to succeed
+    pub valid: Option<bool>,
+    /// The error, the case has to fail with
+    pub expected_error: Option<String>,
+    /// Whether the case may succeed, as well as fail, like an
+    /// `acceptable` case of Project Wycheproof
+    #[serde(default)]
+    pub acceptable: bool,
+}
+
+impl Expectation {
+    /// Whether the case has to fail
+    pub fn is_negative(&self) -> bool {
+        self.valid == Some(false) || self.expected_error.is_some()
+    }
+
+    /// Checks the result of an implementation, errors are
+    /// compared to `expected_error` by their `Display` output.
+    ///
+    /// # Panics
+    /// If the result doesn't match the expectation.
+    #[track_caller]
+    pub fn check<T, E: Display>(&self, result: &Result<T, E>) {
+        self.check_with(result, |err| err.to_string());
+    }
+
+    /// Checks the result of an implementation, errors are compared
+    /// to `expected_error` by their kind, as returned by `kind`.
+    ///
+    /// # Panics
+    /// If the result doesn't match the expectation.
+    #[track_caller]
+    pub fn check_with<T, E, K>(&self, result: &Result<T, E>, kind: impl FnOnce(&E) -> K)
+    where
+        E: Display,
+        K: Display,
+    {
+        if self.valid == Some(true) && self.expected_error.is_some() {
+            panic!(
+                "test case is valid, but expects the error `{}`",
+                self.expected_error.as_deref().unwrap_or_default()
+            );
+        }
+
+        match (result, &self.expected_error) {
+            (Ok(_), _) if self.acceptable => {}
+            (Err(_), None) if self.acceptable => {}
+            (Ok(_), None) if !self.is_negative() => {}
+            (Ok(_), None) => panic!("expected an error, but returned Ok"),
+            (Ok(_), Some(expected)) => panic!("expected the error `{}`, but returned Ok", expected),
+            (Err(err), None) if !self.is_negative() => panic!("expected Ok, but returned Err: {}", err),
+            (Err(_), None) => {}
+            (Err(err), Some(expected)) => {
+                let actual = kind(err).to_string();
+                if actual != *expected {
+                    panic!("expected the error `{}`, but returned Err `{}`: {}", expected, actual, err);
+                }
+            }
+        }
+    }
+}
+
+/// Checks the result of an implementation against the expected outcome
+/// of the test case, read from its `valid` and `expected_error` keys.
+/// Errors are compared to `expected_error` by their `Display` output.
+/// ```no_run
+/// kat::check_outcome(test, &result);
+/// ```
+///
+/// # Panics
+/// If the result doesn't match the expectation, or if the variables of
+/// [test](crate::test) are checked outside of the thread, which runs the case.
+#[track_caller]
+pub fn check_outcome<C, T, E>(test: &C, result: &Result<T, E>)
+where
+    C: Expected + ?Sized,
+    E: Display,
+{
+    test.expectation().check(result);
+}
+
+/// Same as [check_outcome], but errors are compared to
+/// `expected_error` by their kind, as returned by `kind`.
+/// ```no_run
+/// kat::check_outcome_with(test, &result, |err: &MyError| err.kind());
+/// ```
+#[track_caller]
+pub fn check_outcome_with<C, T, E, K>(test: &C, result: &Result<T, E>, kind: impl FnOnce(&E) -> K)
+where
+    C: Expected + ?Sized,
+    E: Display,
+    K: Display,
+{
+    test.expectation().check_with(result, kind);
+}
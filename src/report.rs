@@ -0,0 +1,236 @@
+//! Writes the outcome of every test case into a report file, for CI dashboards.
+//!
+//! `KAT_REPORT=junit-dir:path` writes a JUnit XML file, `KAT_REPORT=json-dir:path`
+//! a json file, for every suite. The path is a directory, the files are named
+//! after the module of the suite, e.g. `path/my_tests.aes.xml`.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Environment variable of the report configuration
+const REPORT_VAR: &str = "KAT_REPORT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Junit,
+    Json,
+}
+
+/// Where and how to write reports
+pub(crate) struct Config {
+    kind: Kind,
+    dir: PathBuf,
+}
+
+impl Config {
+    /// Reads the configuration from the environment, panics on invalid values
+    pub fn from_env() -> Option<Config> {
+        let value = std::env::var(REPORT_VAR).ok()?;
+        if value.trim().is_empty() {
+            return None;
+        }
+
+        let (kind, dir) = match value.trim().split_once(':') {
+            Some(("junit-dir", dir)) if !dir.is_empty() => (Kind::Junit, dir),
+            Some(("json-dir", dir)) if !dir.is_empty() => (Kind::Json, dir),
+            _ => panic!(
+                "Invalid {}: `{}` is no report like `junit-dir:path` or `json-dir:path`",
+                REPORT_VAR, value
+            ),
+        };
+
+        Some(Config {
+            kind,
+            dir: PathBuf::from(dir),
+        })
+    }
+
+    /// Writes the report of a suite
+    pub fn write(&self, suite: &str, duration: Duration, records: &[Record]) -> Result<(), String> {
+        let (extension, content) = match self.kind {
+            Kind::Junit => ("xml", junit(suite, duration, records)),
+            Kind::Json => ("json", json(suite, duration, records)),
+        };
+
+        let filepath = self.dir.join(format!("{}.{}", suite.replace("::", "."), extension));
+        std::fs::create_dir_all(&self.dir)
+            .and_then(|_| std::fs::write(&filepath, content))
+            .map_err(|err| format!("Unable to write report {}: {}", filepath.display(), err))
+    }
+}
+
+/// The outcome of a test case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Status {
+    Passed,
+    Failed,
+    Skipped,
+    ExpectedFailure,
+}
+
+/// A test case of the report
+pub(crate) struct Record {
+    pub file: PathBuf,
+    pub index: usize,
+    pub id: Option<String>,
+    pub name: Option<String>,
+    /// Describes the case, like `id = 17`
+    pub ident: Option<String>,
+    pub status: Status,
+    /// The failure message, or the reason of a skipped case
+    pub message: Option<String>,
+    pub duration: Duration,
+}
+
+impl Record {
+    /// The name of the case, its ident, if it has one
+    fn label(&self) -> String {
+        self.ident.clone().unwrap_or_else(|| format!("case {}", self.index))
+    }
+}
+
+fn junit(suite: &str, duration: Duration, records: &[Record]) -> String {
+    let count = |status| records.iter().filter(|record| record.status == status).count();
+    // Case names are only unique inside their file
+    let several_files = records.iter().any(|record| record.file != records[0].file);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{:.6}\">",
+        escape_xml(suite),
+        records.len(),
+        count(Status::Failed),
+        count(Status::Skipped),
+        duration.as_secs_f64()
+    );
+
+    for record in records {
+        let name = match several_files {
+            true => format!("{}: {}", file_name(&record.file), record.label()),
+            false => record.label(),
+        };
+
+        let _ = write!(
+            xml,
+            "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" time=\"{:.6}\"",
+            escape_xml(&name),
+            escape_xml(suite),
+            escape_xml(&record.file.display().to_string()),
+            record.duration.as_secs_f64()
+        );
+
+        let message = record.message.as_deref().unwrap_or_default();
+        match record.status {
+            Status::Passed => xml.push_str("/>\n"),
+            Status::Failed => {
+                let _ = writeln!(
+                    xml,
+                    ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>",
+                    escape_xml(message.lines().next().unwrap_or_default()),
+                    escape_xml(message)
+                );
+            }
+            Status::Skipped => {
+                let _ = writeln!(
+                    xml,
+                    ">\n      <skipped message=\"{}\"/>\n    </testcase>",
+                    escape_xml(message)
+                );
+            }
+            // JUnit has no expected failures, they count as passed
+            Status::ExpectedFailure => {
+                let _ = writeln!(
+                    xml,
+                    ">\n      <system-out>failed as expected: {}</system-out>\n    </testcase>",
+                    escape_xml(message)
+                );
+            }
+        }
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn json(suite: &str, duration: Duration, records: &[Record]) -> String {
+    let optional = |value: &Option<String>| match value {
+        Some(value) => escape_json(value),
+        None => "null".to_string(),
+    };
+
+    let mut json = String::from("{\n");
+    let _ = writeln!(json, "  \"suite\": {},", escape_json(suite));
+    let _ = writeln!(json, "  \"duration\": {:.6},", duration.as_secs_f64());
+    json.push_str("  \"cases\": [");
+
+    for (i, record) in records.iter().enumerate() {
+        let status = match record.status {
+            Status::Passed => "passed",
+            Status::Failed => "failed",
+            Status::Skipped => "skipped",
+            Status::ExpectedFailure => "expected_failure",
+        };
+
+        json.push_str(if i == 0 { "\n" } else { ",\n" });
+        let _ = write!(
+            json,
+            "    {{\"file\": {}, \"index\": {}, \"id\": {}, \"name\": {}, \"outcome\": \"{}\", \"duration\": {:.6}, \"message\": {}}}",
+            escape_json(&record.file.display().to_string()),
+            record.index,
+            optional(&record.id),
+            optional(&record.name),
+            status,
+            record.duration.as_secs_f64(),
+            optional(&record.message)
+        );
+    }
+
+    json.push_str(if records.is_empty() { "]\n}\n" } else { "\n  ]\n}\n" });
+    json
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters aren't allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Quotes and escapes a json string
+fn escape_json(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,673 @@
+use std::any::Any;
+use std::fmt::{Debug, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::format::{CaseInfo, CaseLayout, Format};
+use crate::report::{self, Record, Status};
+use crate::select::Selection;
+use crate::{outcome, source, update};
+use crate::{KatError, NoGroup, Suite, __DeserializeOwned};
+
+/// private. should not be used directly
+///
+/// Test file configuration, generated by [kat_cfg](crate::kat_cfg).
+pub struct __KatCfg {
+    /// Module of the suite, which names it in reports
+    pub module: &'static str,
+    pub path: &'static [&'static str],
+    pub extension: Option<&'static str>,
+    pub format: Option<Format>,
+    /// Content of the embedded data file
+    pub content: Option<&'static str>,
+    /// Names of the case tables, if [test](crate::test) defines several kinds of cases
+    pub kinds: Option<&'static [&'static str]>,
+    /// Fields of the test cases, which are inherited from the global variables, for every kind
+    pub inherited: &'static [&'static [&'static str]],
+}
+
+impl __KatCfg {
+    /// Joins the configured path, along with its extension
+    fn filepath(&self) -> PathBuf {
+        let mut filepath: PathBuf = self.path.iter().collect();
+        if let Some(extension) = self.extension {
+            filepath.set_extension(extension);
+        }
+        filepath
+    }
+
+    /// Finds the configured data files, which may be a directory or glob.
+    /// Panics, if there are none
+    fn filepaths(&self) -> Vec<PathBuf> {
+        if self.content.is_some() {
+            return vec![self.filepath()];
+        }
+
+        match source::expand(&self.filepath(), self.format) {
+            Ok(filepaths) => filepaths,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Loads a configured data file, panics if it can't be loaded
+    fn load<G, R, T>(&self, filepath: &Path, grouped: bool) -> Suite<G, T, R>
+    where
+        G: __DeserializeOwned,
+        R: __DeserializeOwned,
+        T: __DeserializeOwned,
+    {
+        let layout = CaseLayout {
+            grouped,
+            kinds: self.kinds,
+            inherited: self.inherited,
+        };
+
+        let suite = match self.content {
+            Some(content) => Suite::embedded(filepath, self.format, content, &layout),
+            None => Suite::open(filepath, self.format, &layout),
+        };
+
+        match suite {
+            Ok(suite) => suite,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+/// private. should not be used directly
+pub fn __read_file_as_string(filepath: &Path) -> Result<String, KatError> {
+    if !filepath.is_file() {
+        return Err(KatError::NotFound {
+            path: filepath.to_path_buf(),
+        });
+    }
+
+    std::fs::read_to_string(filepath).map_err(|source| KatError::Io {
+        path: filepath.to_path_buf(),
+        source,
+    })
+}
+
+/// Describes where a test case comes from, for failure messages
+struct CaseContext<'a> {
+    filepath: &'a Path,
+    index: usize,
+    info: CaseInfo,
+}
+
+impl CaseContext<'_> {
+    /// Collects the context of every test case in the file
+    fn all<G, T, R>(suite: &Suite<G, T, R>) -> Vec<CaseContext<'_>> {
+        suite
+            .infos()
+            .iter()
+            .enumerate()
+            .map(|(index, info)| CaseContext {
+                filepath: suite.path(),
+                index,
+                info: info.clone(),
+            })
+            .collect()
+    }
+
+    /// Runs the test case, honouring its directives
+    fn run(&self, case: impl FnOnce()) -> Outcome {
+        if let Some(reason) = &self.info.directives.skip {
+            return Outcome::Skipped(reason.clone());
+        }
+
+        outcome::begin_case(&self.info.expectation);
+        let message = panic::catch_unwind(AssertUnwindSafe(case))
+            .err()
+            .map(|payload| panic_message(payload.as_ref()));
+        outcome::end_case();
+
+        let outcome = match (&self.info.directives.should_panic, message) {
+            (None, None) => Outcome::Passed,
+            (None, Some(message)) => Outcome::Failed(message),
+            (Some(_), None) => Outcome::Failed("test case did not panic as expected".to_string()),
+            (Some(expected), Some(message)) if message.contains(expected.as_str()) => Outcome::Passed,
+            (Some(expected), Some(message)) => Outcome::Failed(format!(
+                "panic did not contain expected string\n  panic message: {:?}\n  expected substring: {:?}",
+                message, expected
+            )),
+        };
+
+        match (&self.info.directives.xfail, outcome) {
+            (None, outcome) => outcome,
+            (Some(_), Outcome::Failed(message)) => Outcome::ExpectedFailure(message),
+            (Some(reason), _) => Outcome::Failed(with_reason("test case passed, but is expected to fail", reason)),
+        }
+    }
+
+    fn describe(&self, message: &str, debug: Option<String>) -> String {
+        let mut origin = Vec::new();
+        if let Some(ident) = &self.info.ident {
+            origin.push(ident.clone());
+        }
+        match self.info.lines {
+            Some((start, end)) => origin.push(format!("{}:{}-{}", self.filepath.display(), start, end)),
+            None => origin.push(self.filepath.display().to_string()),
+        }
+
+        let mut description = format!("case {} ({})", self.index, origin.join(", "));
+
+        description.push_str(": ");
+        description.push_str(&message.replace('\n', "\n    "));
+
+        if let Some(debug) = debug {
+            description.push_str(&format!("\n    test case: {}", debug));
+        }
+
+        description
+    }
+}
+
+/// Outcome of a single test case
+enum Outcome {
+    Passed,
+    Failed(String),
+    Skipped(String),
+    ExpectedFailure(String),
+}
+
+fn with_reason(message: &str, reason: &str) -> String {
+    if reason.is_empty() {
+        message.to_string()
+    } else {
+        format!("{}: {}", message, reason)
+    }
+}
+
+/// private. should not be used directly
+pub struct __DebugCase<'a, T>(pub &'a T);
+
+/// private. should not be used directly
+pub trait __CaseDebug {
+    fn __kat_debug(&self) -> Option<String>;
+}
+
+impl<T: Debug> __CaseDebug for &__DebugCase<'_, T> {
+    fn __kat_debug(&self) -> Option<String> {
+        Some(format!("{:?}", self.0))
+    }
+}
+
+/// private. should not be used directly
+pub trait __CaseNoDebug {
+    fn __kat_debug(&self) -> Option<String>;
+}
+
+impl<T> __CaseNoDebug for __DebugCase<'_, T> {
+    fn __kat_debug(&self) -> Option<String> {
+        None
+    }
+}
+
+/// private. should not be used directly
+///
+/// Formats a test case with its Debug implementation,
+/// if it has one.
+#[macro_export]
+macro_rules! __debug_case {
+    ($case: expr) => {{
+        #[allow(unused_imports)]
+        use kat::{__CaseDebug as _, __CaseNoDebug as _};
+        (&&kat::__DebugCase($case)).__kat_debug()
+    }};
+}
+
+/// private. should not be used directly
+///
+/// Fails the test case, if its runner returned an error.
+#[track_caller]
+pub fn __check_result<E: Display>(result: Result<(), E>) {
+    if let Err(err) = result {
+        panic!("returned Err: {}", err);
+    }
+}
+
+/// private. should not be used directly
+///
+/// Runs a single test case of the data file, used by the [suite](crate::suite)
+/// attribute for its generated test functions.
+pub fn __run_case<G, T, F, D>(cache: &__SuiteCache, filepath: &str, index: usize, runner: F, debug: D)
+where
+    G: __DeserializeOwned + Send + Sync + 'static,
+    T: __DeserializeOwned + Send + Sync + 'static,
+    F: Fn(&G, &T),
+    D: Fn(&T) -> Option<String>,
+{
+    let loaded = cache.0.get_or_init(|| Box::new(CachedSuite::<G, T>::load(filepath)));
+    let cached = match loaded.downcast_ref::<Result<CachedSuite<G, T>, KatError>>() {
+        Some(Ok(cached)) => cached,
+        Some(Err(err)) => panic!("{}", err),
+        None => panic!("The suite of {} was loaded with different types", filepath),
+    };
+    let suite = &cached.suite;
+
+    let (global, _, test) = match suite.iter().nth(index) {
+        Some(case) => case,
+        None => panic!(
+            "Test case {} not found in {}, rebuild the tests after changing the data file",
+            index,
+            suite.path().display()
+        ),
+    };
+
+    // Skipped cases are marked as ignored by the suite attribute instead,
+    // so they still run with `cargo test -- --ignored`
+    if !cached.selected[index] {
+        return;
+    }
+
+    let mut context = CaseContext {
+        filepath: suite.path(),
+        index,
+        info: suite.infos()[index].clone(),
+    };
+    context.info.directives.skip = None;
+
+    update::begin_case();
+    let outcome = context.run(|| runner(global, test));
+    let updates = vec![(index, update::end_case())];
+    if let Err(err) = update::rewrite(suite.path(), suite.format(), suite.kinds(), updates) {
+        panic!("Error: {}", err);
+    }
+
+    if let Outcome::Failed(message) = outcome {
+        let mut description = context.describe(&message, debug(test));
+        // Like the summary of the runner, so the sample can be reproduced
+        if let Some(seed) = cached.seed {
+            description.push_str(&format!("\n  sampled with KAT_SEED={}", seed));
+        }
+        panic!("{}", description);
+    }
+}
+
+/// private. should not be used directly
+///
+/// Data file of a [suite](crate::suite), which is parsed once by the
+/// first of its test functions and shared with the others.
+pub struct __SuiteCache(OnceLock<Box<dyn Any + Send + Sync>>);
+
+impl __SuiteCache {
+    pub const fn new() -> Self {
+        __SuiteCache(OnceLock::new())
+    }
+}
+
+impl Default for __SuiteCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A loaded data file of a [suite](crate::suite), along with its test cases
+struct CachedSuite<G, T> {
+    suite: Suite<G, T>,
+    /// Test cases, which are selected by `KAT_FILTER`, `KAT_SHARD` and `KAT_SAMPLE`
+    selected: Vec<bool>,
+    /// The seed of `KAT_SAMPLE`, if the cases are sampled
+    seed: Option<u64>,
+}
+
+impl<G: __DeserializeOwned, T: __DeserializeOwned> CachedSuite<G, T> {
+    fn load(filepath: &str) -> Result<Self, KatError> {
+        let suite = Suite::<G, T>::load(filepath)?;
+        let selection = Selection::from_env();
+        let selected = selection.select(suite.infos().iter().enumerate());
+
+        Ok(CachedSuite {
+            suite,
+            selected,
+            seed: selection.seed(),
+        })
+    }
+}
+
+/// private. should not be used directly
+///
+/// Runs every test case, even if previous ones failed, and
+/// panics afterwards with a summary of all failed cases.
+pub fn __run_cases<G, T, D, F>(cfg: &__KatCfg, debug: D, runner: F)
+where
+    G: __DeserializeOwned,
+    T: __DeserializeOwned,
+    D: Fn(&T) -> Option<String>,
+    F: Fn(&G, &T),
+{
+    let runner = |global: &G, _: &NoGroup, test: &T| runner(global, test);
+    run_all(cfg, false, debug, |jobs| sequential(jobs, &runner));
+}
+
+/// private. should not be used directly
+///
+/// Same as [__run_cases], for data files with test groups.
+pub fn __run_grouped_cases<G, R, T, D, F>(cfg: &__KatCfg, debug: D, runner: F)
+where
+    G: __DeserializeOwned,
+    R: __DeserializeOwned,
+    T: __DeserializeOwned,
+    D: Fn(&T) -> Option<String>,
+    F: Fn(&G, &R, &T),
+{
+    run_all(cfg, true, debug, |jobs| sequential(jobs, &runner));
+}
+
+/// private. should not be used directly
+///
+/// Same as [__run_cases], but runs the cases across several threads.
+pub fn __run_parallel_cases<G, T, D, F>(cfg: &__KatCfg, debug: D, runner: F)
+where
+    G: __DeserializeOwned + Sync,
+    T: __DeserializeOwned + Sync,
+    D: Fn(&T) -> Option<String>,
+    F: Fn(&G, &T) + Sync,
+{
+    let runner = |global: &G, _: &NoGroup, test: &T| runner(global, test);
+    let threads = threads();
+    run_all(cfg, false, debug, |jobs| parallel(jobs, &runner, threads));
+}
+
+/// private. should not be used directly
+///
+/// Same as [__run_grouped_cases], but runs the cases across several threads.
+pub fn __run_parallel_grouped_cases<G, R, T, D, F>(cfg: &__KatCfg, debug: D, runner: F)
+where
+    G: __DeserializeOwned + Sync,
+    R: __DeserializeOwned + Sync,
+    T: __DeserializeOwned + Sync,
+    D: Fn(&T) -> Option<String>,
+    F: Fn(&G, &R, &T) + Sync,
+{
+    let threads = threads();
+    run_all(cfg, true, debug, |jobs| parallel(jobs, &runner, threads));
+}
+
+/// A selected test case, along with its variables
+struct Job<'a, G, R, T> {
+    /// Position of the data file in the suite
+    file: usize,
+    context: &'a CaseContext<'a>,
+    global: &'a G,
+    group: &'a R,
+    test: &'a T,
+}
+
+impl<G, R, T> Job<'_, G, R, T> {
+    fn run(&self, runner: &impl Fn(&G, &R, &T)) -> Finished {
+        let started = Instant::now();
+        update::begin_case();
+        let outcome = self.context.run(|| runner(self.global, self.group, self.test));
+
+        Finished {
+            outcome,
+            recorded: update::end_case(),
+            duration: started.elapsed(),
+        }
+    }
+}
+
+/// The result of a job
+struct Finished {
+    outcome: Outcome,
+    recorded: update::Recorded,
+    duration: Duration,
+}
+
+/// Runs the jobs one after another
+fn sequential<G, R, T>(jobs: &[Job<'_, G, R, T>], runner: &impl Fn(&G, &R, &T)) -> Vec<Finished> {
+    jobs.iter().map(|job| job.run(runner)).collect()
+}
+
+/// Runs the jobs across `threads` threads, the results keep the order of the jobs
+fn parallel<G, R, T>(
+    jobs: &[Job<'_, G, R, T>],
+    runner: &(impl Fn(&G, &R, &T) + Sync),
+    threads: usize,
+) -> Vec<Finished>
+where
+    G: Sync,
+    R: Sync,
+    T: Sync,
+{
+    let next = AtomicUsize::new(0);
+
+    let mut results: Vec<_> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.min(jobs.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        match jobs.get(index) {
+                            Some(job) => results.push((index, job.run(runner))),
+                            None => return results,
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            // Panics of the cases are caught, so the workers finish
+            .flat_map(|worker| worker.join().expect("worker thread panicked"))
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// The number of threads of parallel suites, set by `KAT_THREADS`,
+/// otherwise the available parallelism
+fn threads() -> usize {
+    match std::env::var("KAT_THREADS") {
+        Ok(threads) if !threads.trim().is_empty() => match threads.trim().parse() {
+            Ok(threads) if threads > 0 => threads,
+            _ => panic!("Invalid KAT_THREADS: `{}` is no positive integer", threads),
+        },
+        _ => thread::available_parallelism().map_or(1, |threads| threads.get()),
+    }
+}
+
+fn run_all<G, R, T, D, E>(cfg: &__KatCfg, grouped: bool, debug: D, execute: E)
+where
+    G: __DeserializeOwned,
+    R: __DeserializeOwned,
+    T: __DeserializeOwned,
+    D: Fn(&T) -> Option<String>,
+    E: for<'a> FnOnce(&'a [Job<'a, G, R, T>]) -> Vec<Finished>,
+{
+    let started = Instant::now();
+    let report_config = report::Config::from_env();
+    let selection = Selection::from_env();
+    let mut report = Report {
+        seed: selection.seed(),
+        ..Default::default()
+    };
+
+    // Every file is loaded up front, since shards and samples span all of them
+    let suites: Vec<Suite<G, T, R>> = cfg
+        .filepaths()
+        .iter()
+        .map(|filepath| cfg.load(filepath, grouped))
+        .collect();
+    let contexts: Vec<Vec<CaseContext<'_>>> = suites.iter().map(CaseContext::all).collect();
+    let mut selected = selection
+        .select(contexts.iter().flatten().map(|context| (context.index, &context.info)))
+        .into_iter();
+
+    let mut jobs = Vec::new();
+    for (file, (suite, contexts)) in suites.iter().zip(&contexts).enumerate() {
+        for (context, (global, group, test)) in contexts.iter().zip(suite.iter()) {
+            if selected.next() == Some(true) {
+                jobs.push(Job {
+                    file,
+                    context,
+                    global,
+                    group,
+                    test,
+                });
+            } else {
+                report.filtered += 1;
+            }
+        }
+    }
+
+    let mut results = jobs.iter().zip(execute(&jobs)).peekable();
+
+    for (file, suite) in suites.iter().enumerate() {
+        let failures = report.failures.len();
+        let mut updates = Vec::new();
+
+        while let Some((job, finished)) = results.next_if(|(job, _)| job.file == file) {
+            updates.push((job.context.index, finished.recorded));
+            report.add(job.context, finished.outcome, finished.duration, || debug(job.test));
+        }
+
+        if let Err(err) = update::rewrite(suite.path(), suite.format(), suite.kinds(), updates) {
+            panic!("Error: {}", err);
+        }
+
+        report.files += 1;
+        if report.failures.len() > failures {
+            report.failed_files += 1;
+        }
+    }
+
+    if let Some(config) = report_config {
+        if let Err(err) = config.write(cfg.module, started.elapsed(), &report.records) {
+            panic!("Error: {}", err);
+        }
+    }
+
+    report.finish();
+}
+
+/// Outcomes of every test case of a run
+#[derive(Default)]
+struct Report {
+    count: usize,
+    files: usize,
+    failed_files: usize,
+    skipped: usize,
+    expected_failures: usize,
+    filtered: usize,
+    /// Seed of the sampled cases
+    seed: Option<u64>,
+    failures: Vec<String>,
+    notes: Vec<String>,
+    /// Every run case, for report files
+    records: Vec<Record>,
+}
+
+impl Report {
+    fn add(
+        &mut self,
+        context: &CaseContext<'_>,
+        outcome: Outcome,
+        duration: Duration,
+        debug: impl FnOnce() -> Option<String>,
+    ) {
+        self.count += 1;
+
+        let (status, message) = match outcome {
+            Outcome::Passed => (Status::Passed, None),
+            Outcome::Failed(message) => {
+                self.failures.push(context.describe(&message, debug()));
+                (Status::Failed, Some(message))
+            }
+            Outcome::Skipped(reason) => {
+                self.skipped += 1;
+                self.notes.push(context.describe(&with_reason("skipped", &reason), None));
+                (Status::Skipped, Some(reason))
+            }
+            Outcome::ExpectedFailure(message) => {
+                self.expected_failures += 1;
+                self.notes.push(context.describe(&format!("failed as expected: {}", message), None));
+                (Status::ExpectedFailure, Some(message))
+            }
+        };
+
+        self.records.push(Record {
+            file: context.filepath.to_path_buf(),
+            index: context.index,
+            id: context.info.id.clone(),
+            name: context.info.name.clone(),
+            ident: context.info.ident.clone(),
+            status,
+            message,
+            duration,
+        });
+    }
+
+    /// Panics with a summary, if any case failed
+    fn finish(self) {
+        let mut tally = Vec::new();
+        if self.skipped > 0 {
+            tally.push(format!("{} skipped", self.skipped));
+        }
+        if self.expected_failures > 0 {
+            tally.push(format!("{} expected to fail", self.expected_failures));
+        }
+        if self.filtered > 0 {
+            tally.push(format!("{} filtered out", self.filtered));
+        }
+        if let Some(seed) = self.seed {
+            tally.push(format!("sampled with KAT_SEED={}", seed));
+        }
+        let tally = if tally.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", tally.join(", "))
+        };
+
+        if self.failures.is_empty() {
+            if !self.notes.is_empty() {
+                let passed = self.count - self.notes.len();
+                eprintln!("{} of {} test cases passed{}:", passed, self.count, tally);
+                for note in self.notes {
+                    eprintln!("  {}", note);
+                }
+            }
+            return;
+        }
+
+        let files = if self.files > 1 {
+            format!(" in {} of {} files", self.failed_files, self.files)
+        } else {
+            String::new()
+        };
+
+        let mut summary = format!(
+            "{} of {} test cases{} failed{}:",
+            self.failures.len(),
+            self.count,
+            files,
+            tally
+        );
+        for description in self.failures.iter().chain(&self.notes) {
+            summary.push_str("\n  ");
+            summary.push_str(description);
+        }
+
+        panic!("{}", summary);
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
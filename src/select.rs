@@ -0,0 +1,247 @@
+//! Selects the test cases of a run, configured by environment variables.
+//!
+//! `KAT_FILTER` holds a comma separated list of filters, a case is run,
+//! if it matches any of them:
+//! - `3`: the case at index 3
+//! - `3..5`, `3..=5`, `..5`, `3..`: the cases in the index range
+//! - `id=17`: the case with the id 17
+//! - `name=abc`: the case with the name abc
+//! - `tag=slow`: the cases with the tag slow
+//!
+//! Of the filtered cases, `KAT_SHARD=i/n` keeps every n-th case, starting
+//! with the i-th, and `KAT_SAMPLE` keeps a random subset of those, either
+//! a percentage like `10%` or a count like `500`. The subset is derived
+//! from `KAT_SEED`, or a random seed, which is reported by the runner.
+
+use std::ops::{Bound, RangeBounds};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::format::CaseInfo;
+
+/// Environment variables of the selection
+const FILTER_VAR: &str = "KAT_FILTER";
+const SHARD_VAR: &str = "KAT_SHARD";
+const SAMPLE_VAR: &str = "KAT_SAMPLE";
+const SEED_VAR: &str = "KAT_SEED";
+
+/// The cases to run
+pub(crate) struct Selection {
+    filters: Vec<Filter>,
+    /// The shard to run, starting at 0, and the number of shards
+    shard: Option<(usize, usize)>,
+    sample: Option<Sample>,
+    seed: u64,
+}
+
+enum Sample {
+    Percent(f64),
+    Count(usize),
+}
+
+enum Filter {
+    Range(Bound<usize>, Bound<usize>),
+    Id(String),
+    Name(String),
+    Tag(String),
+}
+
+impl Selection {
+    /// Reads the selection from the environment, panics on invalid values
+    pub fn from_env() -> Selection {
+        fn read<T>(var: &str, parse: impl FnOnce(&str) -> Result<T, String>) -> Option<T> {
+            let value = std::env::var(var).ok()?;
+            if value.trim().is_empty() {
+                return None;
+            }
+            match parse(value.trim()) {
+                Ok(value) => Some(value),
+                Err(err) => panic!("Invalid {}: {}", var, err),
+            }
+        }
+
+        Selection {
+            filters: read(FILTER_VAR, parse_filters).unwrap_or_default(),
+            shard: read(SHARD_VAR, parse_shard),
+            sample: read(SAMPLE_VAR, parse_sample),
+            seed: read(SEED_VAR, |seed| {
+                seed.parse()
+                    .map_err(|_| format!("`{}` is no unsigned integer", seed))
+            })
+            .unwrap_or_else(random_seed),
+        }
+    }
+
+    /// The seed of the random sample, if the cases are sampled
+    pub fn seed(&self) -> Option<u64> {
+        self.sample.as_ref().map(|_| self.seed)
+    }
+
+    /// Decides for every case, given by its index in the file and its info,
+    /// whether it's run. Cases of several files are selected together.
+    pub fn select<'a>(&self, cases: impl IntoIterator<Item = (usize, &'a CaseInfo)>) -> Vec<bool> {
+        let mut selected: Vec<bool> = cases
+            .into_iter()
+            .map(|(index, info)| {
+                self.filters.is_empty() || self.filters.iter().any(|filter| filter.matches(index, info))
+            })
+            .collect();
+
+        let mut kept: Vec<usize> = (0..selected.len()).filter(|&case| selected[case]).collect();
+
+        if let Some((shard, shards)) = self.shard {
+            kept = kept
+                .into_iter()
+                .enumerate()
+                .filter(|(position, _)| position % shards == shard)
+                .map(|(_, case)| case)
+                .collect();
+        }
+
+        if let Some(sample) = &self.sample {
+            let count = match *sample {
+                Sample::Count(count) => count,
+                Sample::Percent(percent) => (kept.len() as f64 * percent / 100.0).ceil() as usize,
+            };
+
+            // Partial Fisher-Yates shuffle, the first `count` cases are the sample
+            let count = count.min(kept.len());
+            let mut rng = SplitMix64(self.seed);
+            for i in 0..count {
+                let j = i + (rng.next() % (kept.len() - i) as u64) as usize;
+                kept.swap(i, j);
+            }
+            kept.truncate(count);
+        }
+
+        selected.iter_mut().for_each(|selected| *selected = false);
+        for case in kept {
+            selected[case] = true;
+        }
+
+        selected
+    }
+}
+
+fn parse_filters(filters: &str) -> Result<Vec<Filter>, String> {
+    filters
+        .split(',')
+        .map(str::trim)
+        .filter(|filter| !filter.is_empty())
+        .map(Filter::parse)
+        .collect()
+}
+
+/// Parses `i/n`, where `i` starts at 1
+fn parse_shard(shard: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("`{}` is no shard like `1/4`, counting from 1", shard);
+
+    let (index, count) = shard.split_once('/').ok_or_else(invalid)?;
+    let index: usize = index.trim().parse().map_err(|_| invalid())?;
+    let count: usize = count.trim().parse().map_err(|_| invalid())?;
+
+    if index == 0 || index > count {
+        return Err(invalid());
+    }
+
+    Ok((index - 1, count))
+}
+
+/// Parses a percentage like `10%`, or a count like `500`
+fn parse_sample(sample: &str) -> Result<Sample, String> {
+    let invalid = || format!("`{}` is no percentage like `10%`, or count like `500`", sample);
+
+    match sample.strip_suffix('%') {
+        Some(percent) => match percent.trim().parse::<f64>() {
+            Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(Sample::Percent(percent)),
+            _ => Err(invalid()),
+        },
+        None => sample.parse().map(Sample::Count).map_err(|_| invalid()),
+    }
+}
+
+/// A seed, which stays the same for the whole test process,
+/// so separately run cases are sampled alike
+fn random_seed() -> u64 {
+    static SEED: OnceLock<u64> = OnceLock::new();
+
+    *SEED.get_or_init(|| {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default();
+
+        SplitMix64(nanos ^ u64::from(std::process::id())).next()
+    })
+}
+
+/// Small, but good enough random number generator,
+/// see <https://prng.di.unimi.it/splitmix64.c>
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl Filter {
+    fn parse(filter: &str) -> Result<Filter, String> {
+        if let Some((key, value)) = filter.split_once('=') {
+            let value = value.trim().to_string();
+            return match key.trim() {
+                "id" => Ok(Filter::Id(value)),
+                "name" => Ok(Filter::Name(value)),
+                "tag" => Ok(Filter::Tag(value)),
+                // `3..=5`
+                key if key.contains("..") => Self::parse_range(filter),
+                key => Err(format!("unknown filter key `{}`, expected id, name or tag", key)),
+            };
+        }
+
+        Self::parse_range(filter)
+    }
+
+    fn parse_range(filter: &str) -> Result<Filter, String> {
+        let index = |index: &str| {
+            index
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid case index `{}` in `{}`", index.trim(), filter))
+        };
+
+        let (start, end) = match filter.split_once("..") {
+            None => {
+                let index = index(filter)?;
+                return Ok(Filter::Range(Bound::Included(index), Bound::Included(index)));
+            }
+            Some(range) => range,
+        };
+
+        let start = match start.trim() {
+            "" => Bound::Unbounded,
+            start => Bound::Included(index(start)?),
+        };
+
+        let end = match end.strip_prefix('=') {
+            Some(end) => Bound::Included(index(end)?),
+            None if end.trim().is_empty() => Bound::Unbounded,
+            None => Bound::Excluded(index(end)?),
+        };
+
+        Ok(Filter::Range(start, end))
+    }
+
+    fn matches(&self, index: usize, info: &CaseInfo) -> bool {
+        match self {
+            Filter::Range(start, end) => (*start, *end).contains(&index),
+            Filter::Id(id) => info.id.as_ref() == Some(id),
+            Filter::Name(name) => info.name.as_ref() == Some(name),
+            Filter::Tag(tag) => info.tags.contains(tag),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Expands a configured path into the data files it stands for.
+//!
+//! A path is either a single file, a directory, or a glob with `*` and `?`
+//! wildcards in its file name. Of directories and globs, only files with
+//! the extension of a known [Format] are read, unless the format is configured.
+
+use std::path::{Path, PathBuf};
+
+use crate::{Format, KatError};
+
+/// Finds every data file, the path stands for, sorted by path
+pub(crate) fn expand(path: &Path, format: Option<Format>) -> Result<Vec<PathBuf>, KatError> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let (dir, pattern) = if is_glob(&name) {
+        (path.parent().unwrap_or_else(|| Path::new(".")), Some(name.as_str()))
+    } else if path.is_dir() {
+        (path, None)
+    } else {
+        return Ok(vec![path.to_path_buf()]);
+    };
+
+    let entries = std::fs::read_dir(dir).map_err(|source| KatError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|source| KatError::Io {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+
+        let file = entry.path();
+        if !file.is_file() {
+            continue;
+        }
+
+        let matches = pattern.is_none_or(|pattern| {
+            wildcard_match(pattern, &entry.file_name().to_string_lossy())
+        });
+
+        // Directories may hold other files, like READMEs
+        let readable = format.is_some()
+            || file
+                .extension()
+                .and_then(|extension| Format::from_extension(&extension.to_string_lossy()))
+                .is_some();
+
+        if matches && readable {
+            files.push(file);
+        }
+    }
+
+    if files.is_empty() {
+        return Err(KatError::NotFound {
+            path: path.to_path_buf(),
+        });
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn is_glob(name: &str) -> bool {
+    name.contains(['*', '?'])
+}
+
+/// Matches a file name against a pattern, where `*` stands for any
+/// amount of characters, and `?` for a single one
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name position it was tried at
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, tried)) => {
+                    p = star + 1;
+                    n = tried + 1;
+                    backtrack = Some((star, tried + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,224 @@
+use std::path::{Path, PathBuf};
+
+use serde::de::IgnoredAny;
+
+use crate::format::{self, CaseInfo, CaseLayout, Format, Section};
+use crate::{KatError, __Deserialize, __DeserializeOwned, __Deserializer};
+
+/// Group variables of suites without test groups.
+///
+/// Deserializes from anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoGroup;
+
+impl<'de> __Deserialize<'de> for NoGroup {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: __Deserializer<'de>,
+    {
+        IgnoredAny::deserialize(deserializer)?;
+        Ok(NoGroup)
+    }
+}
+
+/// The test cases of a data file, loaded without any macros.
+///
+/// `G` are the global variables, `T` the variables of a test case
+/// and `R` the variables of a test group, for files with test groups.
+/// ```no_run
+/// #[derive(DeriveTable)]
+/// struct Global {
+///     algorithm: String,
+/// }
+///
+/// #[derive(DeriveTable)]
+/// struct Test {
+///     input: types::TomlHex,
+///     expected: types::TomlHex,
+/// }
+///
+/// let suite = kat::Suite::<Global, Test>::load("tests/data/sha256.toml")?;
+/// for test in suite.cases() {
+///     assert_eq!(sha256(&test.input), *test.expected);
+/// }
+/// ```
+pub struct Suite<G, T, R = NoGroup> {
+    filepath: PathBuf,
+    format: Format,
+    layout: CaseLayout,
+    sections: Vec<Section<G, R, T>>,
+    infos: Vec<CaseInfo>,
+}
+
+impl<G, T> Suite<G, T>
+where
+    G: __DeserializeOwned,
+    T: __DeserializeOwned,
+{
+    /// Loads the data file at `path`, its [Format] is derived from the extension.
+    ///
+    /// Relative paths are resolved against the `CARGO_MANIFEST_DIR` of the running
+    /// test, benchmark or build script, if set, otherwise the current directory.
+    /// A path without extension is assumed to be a toml file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KatError> {
+        Self::open(path.as_ref(), None, &CaseLayout::default())
+    }
+
+    /// Loads the data file at `path`, which is read as `format`
+    pub fn load_as(path: impl AsRef<Path>, format: Format) -> Result<Self, KatError> {
+        Self::open(path.as_ref(), Some(format), &CaseLayout::default())
+    }
+}
+
+impl<G, T, R> Suite<G, T, R>
+where
+    G: __DeserializeOwned,
+    R: __DeserializeOwned,
+    T: __DeserializeOwned,
+{
+    /// Loads the data file at `path` with its test groups, which is read as `format`
+    pub fn load_grouped(path: impl AsRef<Path>, format: Format) -> Result<Self, KatError> {
+        let layout = CaseLayout {
+            grouped: true,
+            ..Default::default()
+        };
+        Self::open(path.as_ref(), Some(format), &layout)
+    }
+
+    pub(crate) fn open(path: &Path, format: Option<Format>, layout: &CaseLayout) -> Result<Self, KatError> {
+        let (filepath, format) = resolve(path, format, layout)?;
+        let content = crate::__read_file_as_string(&filepath)?;
+        Self::parse(filepath, format, &content, layout)
+    }
+
+    /// Parses the content of a data file, which was embedded at compile time
+    pub(crate) fn embedded(
+        path: &Path,
+        format: Option<Format>,
+        content: &str,
+        layout: &CaseLayout,
+    ) -> Result<Self, KatError> {
+        let (filepath, format) = resolve(path, format, layout)?;
+        Self::parse(filepath, format, content, layout)
+    }
+
+    fn parse(filepath: PathBuf, format: Format, content: &str, layout: &CaseLayout) -> Result<Self, KatError> {
+        let sections = match format::load(format, content, layout) {
+            Ok(sections) => sections,
+            Err(err) => return Err(KatError::parse(filepath, format, err)),
+        };
+
+        let infos = match format::case_infos(format, content, layout.kinds, Section::cases(&sections).count()) {
+            Ok(infos) => infos,
+            Err(err) => return Err(KatError::parse(filepath, format, err)),
+        };
+        Ok(Suite {
+            filepath,
+            format,
+            layout: *layout,
+            sections,
+            infos,
+        })
+    }
+}
+
+impl<G, T, R> Suite<G, T, R> {
+    /// The resolved path of the data file
+    pub fn path(&self) -> &Path {
+        &self.filepath
+    }
+
+    /// The format, the data file was read as
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// The global variables.
+    ///
+    /// Files like NIST CAVP files consist of several sections with their own
+    /// global variables, use [iter](Suite::iter) for them. This returns the
+    /// global variables of the first section.
+    pub fn global(&self) -> Option<&G> {
+        self.sections.first().map(|section| &section.global)
+    }
+
+    /// Iterates over every test case
+    pub fn cases(&self) -> impl Iterator<Item = &T> {
+        self.iter().map(|(_, _, test)| test)
+    }
+
+    /// Iterates over every test case, along with its global and group variables
+    pub fn iter(&self) -> impl Iterator<Item = (&G, &R, &T)> {
+        Section::cases(&self.sections)
+    }
+
+    /// The number of test cases
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Whether the data file has no test cases
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Identifies every test case inside the data file
+    pub(crate) fn infos(&self) -> &[CaseInfo] {
+        &self.infos
+    }
+
+    /// Names of the case tables, if there are several kinds of test cases
+    pub(crate) fn kinds(&self) -> Option<&'static [&'static str]> {
+        self.layout.kinds
+    }
+}
+
+/// Resolves the path and format of a data file
+fn resolve(path: &Path, format: Option<Format>, layout: &CaseLayout) -> Result<(PathBuf, Format), KatError> {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().into_owned());
+
+    let format = match (format, &extension) {
+        (Some(format), _) => format,
+        (None, None) => Format::Toml,
+        (None, Some(extension)) => Format::from_extension(extension).ok_or_else(|| {
+            KatError::validation(format!(
+                "Unsupported file extension \".{}\", configure the format explicitly",
+                extension
+            ))
+        })?,
+    };
+
+    if layout.grouped && !format.has_groups() {
+        return Err(KatError::validation(format!(
+            "{} files don't support test groups",
+            format
+        )));
+    }
+
+    if layout.kinds.is_some() && !format.is_toml_like() {
+        return Err(KatError::validation(format!(
+            "{} files don't support several kinds of test cases",
+            format
+        )));
+    }
+
+    if layout.inherited.iter().any(|inherited| !inherited.is_empty()) && !format.is_toml_like() {
+        return Err(KatError::validation(format!(
+            "{} files don't support inherited fields",
+            format
+        )));
+    }
+
+    let mut filepath = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(manifest_dir) if path.is_relative() => PathBuf::from(manifest_dir).join(path),
+        _ => path.to_path_buf(),
+    };
+
+    if extension.is_none() {
+        filepath.set_extension(format.extension());
+    }
+
+    Ok((filepath, format))
+}
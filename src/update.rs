@@ -0,0 +1,204 @@
+//! Golden file updates.
+//!
+//! With `KAT_UPDATE=1`, [expect](crate::expect) records the actual values
+//! of the running test case, instead of comparing them. After the cases ran,
+//! the recorded values are written back into the toml data file.
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::format::Format;
+
+/// Fields of a test case, along with their actual values
+pub(crate) type Recorded = Vec<(&'static str, toml::Value)>;
+
+thread_local! {
+    /// Values recorded by the test case, that runs on this thread
+    static RECORDED: RefCell<Option<Recorded>> = const { RefCell::new(None) };
+}
+
+/// Serializes rewrites of data files, which are shared by
+/// the parallel tests of the [suite](crate::suite) attribute
+static REWRITE: Mutex<()> = Mutex::new(());
+
+/// Whether the tests run in update mode
+pub(crate) fn enabled() -> bool {
+    std::env::var_os("KAT_UPDATE").is_some_and(|value| value == "1")
+}
+
+/// Starts recording the values of a test case, if in update mode
+pub(crate) fn begin_case() {
+    if enabled() {
+        RECORDED.with(|recorded| *recorded.borrow_mut() = Some(Vec::new()));
+    }
+}
+
+/// Stops recording and returns the values recorded by the test case
+pub(crate) fn end_case() -> Recorded {
+    RECORDED
+        .with(|recorded| recorded.borrow_mut().take())
+        .unwrap_or_default()
+}
+
+/// private. should not be used directly
+///
+/// Compares a field of the test case with its actual value,
+/// or records the actual value in update mode.
+#[track_caller]
+pub fn __expect<E, A>(expected: &E, actual: A, field: &'static str)
+where
+    E: From<A> + PartialEq + Debug + Serialize,
+{
+    let actual = E::from(actual);
+
+    let recording = RECORDED.with(|recorded| match recorded.borrow_mut().as_mut() {
+        Some(values) => {
+            let value = toml::Value::try_from(&actual).unwrap_or_else(|err| {
+                panic!("Unable to write the actual value of `{}` to toml: {}", field, err)
+            });
+            values.retain(|(recorded, _)| *recorded != field);
+            values.push((field, value));
+            true
+        }
+        None => false,
+    });
+
+    if !recording {
+        assert!(
+            *expected == actual,
+            "`{}` doesn't match\n  expected: {:?}\n    actual: {:?}",
+            field,
+            expected,
+            actual
+        );
+    }
+}
+
+/// Writes the recorded values back into the data file.
+///
+/// `updates` holds the index of the test case and its recorded values,
+/// `kinds` the names of the case tables, if there are several kinds of cases.
+/// Only the recorded keys are touched, comments and formatting are kept.
+pub(crate) fn rewrite(
+    filepath: &Path,
+    format: Format,
+    kinds: Option<&[&str]>,
+    updates: Vec<(usize, Recorded)>,
+) -> Result<(), String> {
+    if updates.iter().all(|(_, values)| values.is_empty()) {
+        return Ok(());
+    }
+
+    if format != Format::Toml {
+        return Err(format!("KAT_UPDATE only supports toml files, not {} files", format));
+    }
+
+    let _guard = REWRITE.lock().unwrap_or_else(|err| err.into_inner());
+
+    let content = crate::__read_file_as_string(filepath).map_err(|err| err.to_string())?;
+    let mut document: DocumentMut = content
+        .parse()
+        .map_err(|err| format!("Unable to parse toml: {}", err))?;
+
+    let mut tests = match kinds {
+        Some(kinds) => kind_tables(&mut document, kinds),
+        None => case_tables(&mut document),
+    };
+    if tests.is_empty() {
+        return Err("Unable to find the [[test]] tables".to_string());
+    }
+
+    for (index, values) in updates {
+        let table = tests
+            .get_mut(index)
+            .ok_or_else(|| format!("Unable to find the [[test]] table of case {}", index))?;
+
+        for (key, value) in values {
+            let mut value = to_edit_value(key, &value)?;
+
+            match table.get_mut(key) {
+                Some(item) => {
+                    // Keep comments and whitespace around the replaced value
+                    if let Some(old) = item.as_value() {
+                        *value.decor_mut() = old.decor().clone();
+                    }
+                    *item = Item::Value(value);
+                }
+                None => {
+                    table.insert(key, Item::Value(value));
+                }
+            }
+        }
+    }
+
+    let updated = document.to_string();
+    if updated != content {
+        std::fs::write(filepath, updated)
+            .map_err(|err| format!("Unable to write {}: {}", filepath.display(), err))?;
+    }
+
+    Ok(())
+}
+
+/// Collects the tables of every test case, the `[[test]]` tables first,
+/// followed by the `[[group.test]]` tables of every group
+fn case_tables(document: &mut DocumentMut) -> Vec<&mut Table> {
+    let mut tests = Vec::new();
+    let mut grouped = Vec::new();
+
+    for (key, item) in document.as_table_mut().iter_mut() {
+        let Some(tables) = item.as_array_of_tables_mut() else {
+            continue;
+        };
+
+        match key.get() {
+            "test" => tests.extend(tables.iter_mut()),
+            "group" => grouped.extend(
+                tables
+                    .iter_mut()
+                    .filter_map(|group| group.get_mut("test").and_then(Item::as_array_of_tables_mut))
+                    .flat_map(|tables| tables.iter_mut()),
+            ),
+            _ => {}
+        }
+    }
+
+    tests.extend(grouped);
+    tests
+}
+
+/// Collects the tables of every test case of one of `kinds`, in file order
+fn kind_tables<'a>(document: &'a mut DocumentMut, kinds: &[&str]) -> Vec<&'a mut Table> {
+    let mut tests: Vec<&mut Table> = document
+        .as_table_mut()
+        .iter_mut()
+        .filter(|(key, _)| kinds.contains(&key.get()))
+        .filter_map(|(_, item)| item.as_array_of_tables_mut())
+        .flat_map(|tables| tables.iter_mut())
+        .collect();
+
+    tests.sort_by_key(|table| table.position());
+    tests
+}
+
+fn to_edit_value(key: &str, value: &toml::Value) -> Result<toml_edit::Value, String> {
+    let mut table = toml::value::Table::new();
+    table.insert(key.to_string(), value.clone());
+
+    let serialized = toml::to_string(&table).map_err(|err| err.to_string())?;
+    let mut document: DocumentMut = serialized.parse().map_err(|err| format!("{}", err))?;
+
+    // Tables are written as inline tables, to stay inside the [[test]] table
+    match document.remove(key).map(Item::into_value) {
+        Some(Ok(mut value)) => {
+            value.decor_mut().clear();
+            Ok(value)
+        }
+        _ => Err(format!("Unable to write the actual value of `{}` as an inline toml value", key)),
+    }
+}
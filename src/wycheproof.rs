@@ -0,0 +1,195 @@
+//! Helpers for [Project Wycheproof](https://github.com/C2SP/wycheproof) test vectors.
+//!
+//! Wycheproof files nest their test cases in test groups, which
+//! carry parameters shared by their cases.
+//! ```text
+//! {
+//!   "algorithm": "AES-GCM",
+//!   "numberOfTests": 1,
+//!   "testGroups": [
+//!     {
+//!       "ivSize": 96,
+//!       "keySize": 128,
+//!       "tests": [
+//!         { "tcId": 1, "key": "...", "result": "valid", "flags": [] }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//! The top level keys of the file are read as global variables,
+//! the keys of a test group as group variables and each entry of
+//! `tests` as a test case.
+//!
+//! Every test case states, whether an implementation has to accept
+//! or reject it in its `result` field, which can be deserialized
+//! as [TestResult] and be checked against the implementations decision.
+
+use std::fmt::Debug;
+
+use crate::error::ParseError;
+use crate::format::{self, CaseInfo, Group, Section};
+use crate::outcome::Expectation;
+use crate::__DeserializeOwned;
+
+#[derive(serde_derive::Deserialize)]
+#[serde(bound = "G: __DeserializeOwned, R: __DeserializeOwned, T: __DeserializeOwned")]
+struct Layout<G, R, T> {
+    #[serde(flatten)]
+    global: G,
+
+    #[serde(rename = "testGroups")]
+    groups: Vec<GroupLayout<R, T>>,
+}
+
+#[derive(serde_derive::Deserialize)]
+#[serde(bound = "R: __DeserializeOwned, T: __DeserializeOwned")]
+struct GroupLayout<R, T> {
+    #[serde(flatten)]
+    group: R,
+
+    tests: Vec<T>,
+}
+
+#[derive(serde_derive::Deserialize)]
+struct MetaLayout {
+    #[serde(rename = "testGroups")]
+    groups: Vec<MetaGroup>,
+}
+
+#[derive(serde_derive::Deserialize)]
+struct MetaGroup {
+    tests: Vec<CaseMeta>,
+}
+
+#[derive(serde_derive::Deserialize)]
+struct CaseMeta {
+    #[serde(rename = "tcId")]
+    tc_id: Option<serde_json::Value>,
+
+    #[serde(default)]
+    flags: Vec<String>,
+
+    result: Option<String>,
+}
+
+/// Deserializes a Wycheproof file.
+///
+/// Without `grouped`, the test groups are merged into a single one.
+pub(crate) fn load<G, R, T>(content: &str, grouped: bool) -> Result<Vec<Section<G, R, T>>, ParseError>
+where
+    G: __DeserializeOwned,
+    R: __DeserializeOwned,
+    T: __DeserializeOwned,
+{
+    let layout: Layout<G, R, T> = format::json_from_str(content)?;
+
+    if !grouped {
+        let tests = layout.groups.into_iter().flat_map(|group| group.tests).collect();
+        return Ok(vec![Section::ungrouped(layout.global, tests)?]);
+    }
+
+    Ok(vec![Section {
+        global: layout.global,
+        groups: layout
+            .groups
+            .into_iter()
+            .map(|group| Group {
+                group: group.group,
+                tests: group.tests,
+            })
+            .collect(),
+    }])
+}
+
+/// Collects the `tcId` of every test case, along with its expected outcome
+pub(crate) fn case_infos(content: &str) -> Result<Vec<CaseInfo>, ParseError> {
+    let layout: MetaLayout = format::json_from_str(content)?;
+
+    let infos = layout
+        .groups
+        .into_iter()
+        .flat_map(|group| group.tests)
+        .map(|case| CaseInfo {
+            ident: case.tc_id.as_ref().map(|id| format!("tcId = {}", id)),
+            id: case.tc_id.map(|id| id.to_string()),
+            // Flags name the property a case tests, so they act as tags
+            tags: case.flags,
+            expectation: Expectation {
+                valid: match case.result.as_deref() {
+                    Some("valid") => Some(true),
+                    Some("invalid") => Some(false),
+                    _ => None,
+                },
+                expected_error: None,
+                acceptable: case.result.as_deref() == Some("acceptable"),
+            },
+            ..Default::default()
+        })
+        .collect();
+
+    Ok(infos)
+}
+
+/// Expected outcome of a Wycheproof test case, its `result` field
+#[derive(serde_derive::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestResult {
+    /// The input is valid and has to be accepted
+    Valid,
+
+    /// The input is invalid and has to be rejected
+    Invalid,
+
+    /// The input may be accepted or rejected, e.g. because
+    /// it uses a legacy or weak parameter. The test case `flags`
+    /// name the reason
+    Acceptable,
+}
+
+impl TestResult {
+    /// Checks the accept/reject decision of an implementation.
+    ///
+    /// # Panics
+    /// If a valid input was rejected, or an invalid one accepted.
+    pub fn check(&self, accepted: bool) {
+        match (self, accepted) {
+            (TestResult::Valid, false) => panic!("valid input was rejected"),
+            (TestResult::Invalid, true) => panic!("invalid input was accepted"),
+            _ => {}
+        }
+    }
+
+    /// Checks the result of an implementation, where `Ok` means accepted
+    /// and `Err` rejected.
+    ///
+    /// # Panics
+    /// If a valid input was rejected, or an invalid one accepted.
+    pub fn check_result<T, E: Debug>(&self, result: &Result<T, E>) {
+        match (self, result) {
+            (TestResult::Valid, Err(err)) => panic!("valid input was rejected: {:?}", err),
+            (TestResult::Invalid, Ok(_)) => panic!("invalid input was accepted"),
+            _ => {}
+        }
+    }
+
+    /// Checks the accept/reject decision of an implementation, where
+    /// acceptable inputs have to be accepted, if any of their `flags` is
+    /// in `accepted_flags`, and rejected otherwise.
+    ///
+    /// # Panics
+    /// If the decision doesn't match the expectation.
+    pub fn check_flagged(&self, accepted: bool, flags: &[String], accepted_flags: &[&str]) {
+        match self {
+            TestResult::Acceptable => {
+                let expected = flags.iter().any(|flag| accepted_flags.contains(&flag.as_str()));
+                match (expected, accepted) {
+                    (true, false) => panic!("acceptable input with flags {:?} was rejected", flags),
+                    (false, true) => panic!("acceptable input with flags {:?} was accepted", flags),
+                    _ => {}
+                }
+            }
+            _ => self.check(accepted),
+        }
+    }
+}
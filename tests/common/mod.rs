@@ -0,0 +1,22 @@
+//! Helpers of the tests, which configure kat by environment variables.
+//!
+//! The variables are read by every suite of the test binary, so a test,
+//! which sets them, must be the only one of its binary. Its suites are
+//! marked with `#[ignore]`, and run by the test through `kat_tests::tests`.
+
+/// Runs `run` with the environment variables set, and
+/// returns its panic message, if it panicked.
+pub fn with_env(vars: &[(&str, &str)], run: impl FnOnce()) -> Result<(), String> {
+    for (var, value) in vars {
+        std::env::set_var(var, value);
+    }
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(run));
+    for (var, _) in vars {
+        std::env::remove_var(var);
+    }
+
+    result.map_err(|payload| match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast_ref::<&str>().map(|message| message.to_string()).unwrap_or_default(),
+    })
+}
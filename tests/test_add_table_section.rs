@@ -1,4 +1,3 @@
-use kat;
 use kat::DeriveTable;
 
 #[derive(DeriveTable)]
@@ -0,0 +1,51 @@
+mod by_extension {
+    kat::kat_cfg!(tests / data / data_cavp.rsp);
+
+    kat::global! {
+        section: String
+    }
+
+    kat::test! {
+        count: usize,
+        key: String,
+        plaintext: String,
+        ciphertext: String
+    }
+
+    kat::run! {
+        |global, test| -> {
+            assert!(global.section == "ENCRYPT" || global.section == "DECRYPT");
+            assert!(test.count < 2);
+            assert_eq!(test.key, "00000000000000000000000000000000");
+            assert_eq!(test.plaintext.len(), 32);
+            assert_eq!(test.ciphertext.len(), 32);
+        }
+    }
+}
+
+mod by_format {
+    kat::kat_cfg!(tests / data / data_cavp_params, format = Cavp);
+
+    kat::global! {
+        keylen: usize,
+        ivlen: usize,
+        ptlen: usize
+    }
+
+    kat::test! {
+        count: u32,
+        key: String,
+        iv: String,
+        pt: String,
+        fail: Option<bool>
+    }
+
+    kat::run! {
+        |global, test| -> {
+            assert_eq!(test.key.len() * 4, global.keylen);
+            assert_eq!(test.iv.len() * 4, global.ivlen);
+            assert_eq!(test.pt.len() * 4, global.ptlen);
+            assert_eq!(test.fail.is_some(), global.keylen == 128 && test.count == 0);
+        }
+    }
+}
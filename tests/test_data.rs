@@ -1,5 +1,3 @@
-use kat;
-
 kat::kat_cfg!(tests / data / data);
 
 kat::global! {
@@ -1,3 +1,5 @@
+#![allow(clippy::approx_constant)]
+
 use kat::*;
 
 kat_cfg!(tests/data/data_de_all_types);
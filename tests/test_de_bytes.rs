@@ -0,0 +1,49 @@
+use kat::types::{TomlBase64, TomlHex, TomlHexArray};
+
+mod valid {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_de_bytes);
+
+    kat::global! {
+        key: TomlHexArray<16>,
+        nonce: TomlHex,
+        digest: TomlBase64
+    }
+
+    kat::test! {
+        hex: TomlHex,
+        hex_array: TomlHexArray<4>,
+        base64: TomlBase64
+    }
+
+    kat::run! {
+        |global, test| -> {
+            assert_eq!(global.key.bytes, core::array::from_fn::<u8, 16, _>(|i| i as u8));
+            assert_eq!(*global.nonce, (0..12).collect::<Vec<u8>>());
+            assert_eq!(*global.digest, [0, 1, 2, 3]);
+
+            assert_eq!(*test.hex, [0xde, 0xad, 0xbe, 0xef]);
+            assert_eq!(*test.hex_array, [0xca, 0xfe, 0xba, 0xbe]);
+            assert_eq!(test.base64.bytes, test.hex.bytes);
+        }
+    }
+}
+
+mod invalid {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_de_bytes_invalid);
+
+    kat::global! {}
+
+    kat::test! {
+        #[allow(dead_code)]
+        hex: TomlHex
+    }
+
+    kat::run! {
+        #[should_panic(expected = "invalid hex character 'g' at offset 4 for key `test.hex`")]
+        |_global, _test| -> {}
+    }
+}
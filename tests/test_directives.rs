@@ -0,0 +1,59 @@
+use kat::DeriveTable;
+
+#[derive(DeriveTable)]
+struct Global {}
+
+#[derive(DeriveTable)]
+struct Test {
+    action: String,
+}
+
+fn act(test: &Test) {
+    match test.action.as_str() {
+        "pass" => {}
+        "panic" => panic!("input rejected"),
+        _ => panic!("wrong output"),
+    }
+}
+
+#[kat::suite("tests/data/data_directives.toml")]
+fn suite(_global: &Global, test: &Test) {
+    act(test);
+}
+
+mod passing {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_directives);
+
+    kat::global! {}
+
+    kat::test! {
+        action: String
+    }
+
+    kat::run! {
+        |_global, test| -> {
+            act(&Test { action: test.action.clone() });
+        }
+    }
+}
+
+mod failing {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_directives_failing);
+
+    kat::global! {}
+
+    kat::test! {
+        action: String
+    }
+
+    kat::run! {
+        #[should_panic(expected = "3 of 4 test cases failed (1 skipped):")]
+        |_global, test| -> {
+            act(&Test { action: test.action.clone() });
+        }
+    }
+}
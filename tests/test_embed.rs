@@ -0,0 +1,95 @@
+mod by_extension {
+    kat::kat_cfg!(tests / data / data, embed);
+
+    kat::global! {
+        name: String,
+        value: usize
+    }
+
+    kat::test! {
+        test_name: String,
+        value: usize
+    }
+
+    kat::run! {
+        |global, test| -> {
+            assert_eq!(global.name, "GLOBAL");
+            assert_eq!(global.value, 69);
+            assert_eq!(test.test_name, "TEST");
+            assert_eq!(test.value, 420);
+        }
+    }
+}
+
+mod by_format {
+    kat::kat_cfg!(tests / data / data_cavp_params, format = Cavp, embed);
+
+    kat::global! {
+        keylen: usize
+    }
+
+    kat::test! {
+        count: u32
+    }
+
+    kat::run! {
+        |global, test| -> {
+            assert!(global.keylen == 128 || global.keylen == 256);
+            assert!(test.count < 2);
+        }
+    }
+}
+
+kat::kat_suite!(failing {
+    cfg: tests / data / data_failing_cases.toml, embed,
+    global: {},
+    test: {
+        name: String,
+        valid: bool
+    },
+    run: #[should_panic(expected = "data_failing_cases.toml:11-13): third is broken")] |_global, test| {
+        assert!(test.valid, "{} is broken", test.name);
+    }
+});
+
+mod literal {
+    kat::kat_cfg!("tests/data/data", embed);
+
+    kat::global! {
+        name: String,
+        value: usize
+    }
+
+    kat::test! {
+        test_name: String,
+        value: usize
+    }
+
+    kat::run! {
+        |global, test| -> {
+            assert_eq!(global.name, "GLOBAL");
+            assert_eq!(global.value, 69);
+            assert_eq!(test.test_name, "TEST");
+            assert_eq!(test.value, 420);
+        }
+    }
+}
+
+mod literal_by_format {
+    kat::kat_cfg!("tests/data/data_cavp_params", format = Cavp, embed);
+
+    kat::global! {
+        keylen: usize
+    }
+
+    kat::test! {
+        count: u32
+    }
+
+    kat::run! {
+        |global, test| -> {
+            assert!(global.keylen == 128 || global.keylen == 256);
+            assert!(test.count < 2);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+kat::kat_cfg!(tests / data / data_failing_cases);
+
+kat::global! {}
+
+kat::test! {
+    #![derive(Debug)]
+
+    name: String,
+    valid: bool
+}
+
+kat::run! {
+    #[should_panic(expected = "data_failing_cases.toml:11-13): third is broken
+    test case: __KatTest { name: \"third\", valid: false }")]
+    |_global, test| -> {
+        assert!(test.valid, "{} is broken", test.name);
+    }
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use std::sync::Mutex;
+
+/// Ids of the cases, which were run
+static RUN: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+kat::kat_cfg!(tests / data / data_filter);
+
+kat::global! {}
+
+kat::test! {
+    id: Option<usize>
+}
+
+// Run by `filter_cases` with the filter set
+kat::run! {
+    #[ignore]
+    |_global, test| -> {
+        RUN.lock().unwrap().push(test.id.unwrap_or(0));
+    }
+}
+
+/// Runs the suite with `filter`, returns the ids of the cases, which were run
+fn run(filter: &str) -> Result<Vec<usize>, String> {
+    RUN.lock().unwrap().clear();
+    common::with_env(&[("KAT_FILTER", filter)], kat_tests::tests)?;
+    Ok(std::mem::take(&mut *RUN.lock().unwrap()))
+}
+
+// A single test, since the filter is read from the environment
+#[test]
+fn filter_cases() {
+    assert_eq!(run("").unwrap(), [10, 17, 23, 42, 0]);
+    assert_eq!(run("1").unwrap(), [17]);
+    assert_eq!(run("1..3").unwrap(), [17, 23]);
+    assert_eq!(run("1..=3").unwrap(), [17, 23, 42]);
+    assert_eq!(run("3..").unwrap(), [42, 0]);
+    assert_eq!(run("..1").unwrap(), [10]);
+    assert_eq!(run("id=17").unwrap(), [17]);
+    assert_eq!(run("name=unnamed").unwrap(), [0]);
+    assert_eq!(run("tag=slow").unwrap(), [23, 42]);
+    assert_eq!(run("tag=edge case").unwrap(), [23]);
+    assert_eq!(run("id=10, 4, tag=slow").unwrap(), [10, 23, 42, 0]);
+    assert!(run("id=99").unwrap().is_empty());
+
+    assert_eq!(
+        run("color=red").unwrap_err(),
+        "Invalid KAT_FILTER: unknown filter key `color`, expected id, name or tag"
+    );
+    assert_eq!(run("3..x").unwrap_err(), "Invalid KAT_FILTER: invalid case index `x` in `3..x`");
+}
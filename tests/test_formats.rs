@@ -0,0 +1,44 @@
+macro_rules! data_test {
+    () => {
+        kat::global! {
+            name: String,
+            value: usize
+        }
+
+        kat::test! {
+            id: usize,
+            test_name: String,
+            value: usize
+        }
+
+        kat::run! {
+            |global, test| -> {
+                assert_eq!(global.name, "GLOBAL");
+                assert_eq!(global.value, 69);
+                assert!(test.id < 2);
+                assert_eq!(test.test_name, "TEST");
+                assert_eq!(test.value, 420);
+            }
+        }
+    };
+}
+
+mod json {
+    kat::kat_cfg!(tests / data / data.json);
+    data_test!();
+}
+
+mod yaml {
+    kat::kat_cfg!(tests / data / data.yml);
+    data_test!();
+}
+
+mod ron {
+    kat::kat_cfg!(tests / data / data.ron);
+    data_test!();
+}
+
+mod explicit_format {
+    kat::kat_cfg!(tests / data / data.yml, format = Yaml);
+    data_test!();
+}
@@ -0,0 +1,91 @@
+use kat::{Format, NoGroup, Suite};
+
+macro_rules! key_size_test {
+    ($($cfg: tt)*) => {
+        kat::kat_cfg!($($cfg)*);
+
+        kat::global! {
+            algorithm: String
+        }
+
+        kat::group! {
+            key_size: usize
+        }
+
+        kat::test! {
+            id: usize,
+            key: kat::types::TomlHex
+        }
+
+        kat::run! {
+            |global, group, test| -> {
+                assert_eq!(global.algorithm, "AES");
+                assert_eq!(test.key.len() * 8, group.key_size, "key of case {}", test.id);
+            }
+        }
+    };
+}
+
+mod toml {
+    key_size_test!(tests / data / data_groups);
+}
+
+#[cfg(feature = "json")]
+mod json {
+    key_size_test!(tests / data / data_groups.json);
+}
+
+mod failing {
+    kat::kat_cfg!(tests / data / data_groups);
+
+    kat::global! {}
+
+    kat::group! {
+        key_size: usize
+    }
+
+    kat::test! {}
+
+    kat::run! {
+        #[should_panic(expected = "data_groups.toml:20-22): key size 256")]
+        |_global, group, _test| -> {
+            assert!(group.key_size < 256, "key size {}", group.key_size);
+        }
+    }
+}
+
+#[derive(kat::DeriveTable)]
+struct Global {
+    algorithm: String,
+}
+
+#[derive(kat::DeriveTable, Debug, PartialEq)]
+struct Group {
+    key_size: usize,
+}
+
+#[derive(kat::DeriveTable)]
+struct Test {
+    id: usize,
+}
+
+#[test]
+fn load_groups() {
+    let suite = Suite::<Global, Test, Group>::load_grouped("tests/data/data_groups", Format::Toml).unwrap();
+    let cases: Vec<_> = suite
+        .iter()
+        .map(|(global, group, test)| (global.algorithm.as_str(), group.key_size, test.id))
+        .collect();
+    assert_eq!(cases, [("AES", 128, 1), ("AES", 128, 2), ("AES", 256, 3)]);
+
+    // Test cases outside of groups would be lost
+    let err = Suite::<Global, Test, NoGroup>::load_grouped("tests/data/data_groups_mixed", Format::Toml)
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .ends_with("Test cases have to be inside of a group, if the runner takes test groups"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,93 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Fixture, which logs the calls of the hooks
+#[derive(Default)]
+struct Log(Mutex<Vec<String>>);
+
+impl Log {
+    fn push(&self, entry: impl Into<String>) {
+        self.0.lock().unwrap().push(entry.into());
+    }
+}
+
+mod with_fixture {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_filter);
+
+    kat::global! {}
+
+    kat::test! {
+        id: Option<usize>
+    }
+
+    kat::run! {
+        setup: || -> {
+            let log = Log::default();
+            log.push("setup");
+            log
+        },
+        before_each: |_global, test, log| -> {
+            log.push(format!("before {:?}", test.id));
+        },
+        after_each: |_global, test, log| -> {
+            log.push(format!("after {:?}", test.id));
+        },
+        teardown: |log| -> {
+            let log = log.0.into_inner().unwrap();
+            assert_eq!(log.len(), 1 + 3 * 5);
+            assert_eq!(log[..4], ["setup", "before Some(10)", "run Some(10)", "after Some(10)"]);
+            assert_eq!(log[13..], ["before None", "run None", "after None"]);
+        },
+        |_global, test, log| -> {
+            log.push(format!("run {:?}", test.id));
+        }
+    }
+}
+
+static AFTER_EACH: AtomicUsize = AtomicUsize::new(0);
+
+mod failing {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_sample);
+
+    kat::global! {}
+
+    kat::test! {
+        id: usize
+    }
+
+    // Panics of the teardown would change the panic message
+    kat::run! {
+        #[should_panic(expected = "1 of 12 test cases failed:")]
+        before_each: |_global, test| -> {
+            assert!(test.id < 12);
+        },
+        after_each: |_global, _test| -> {
+            AFTER_EACH.fetch_add(1, Ordering::Relaxed);
+        },
+        teardown: || -> {
+            assert_eq!(AFTER_EACH.load(Ordering::Relaxed), 12);
+        },
+        |_global, test| -> {
+            assert_ne!(test.id, 5);
+        }
+    }
+}
+
+kat::kat_suite!(parallel_suite {
+    cfg: tests / data / data_sample,
+    global: {},
+    test: {},
+    setup: || {
+        AtomicUsize::new(0)
+    },
+    teardown: |count| {
+        assert_eq!(count.into_inner(), 12);
+    },
+    run: parallel |_global, _test, count| {
+        count.fetch_add(1, Ordering::Relaxed);
+    },
+});
@@ -0,0 +1,136 @@
+mod toml {
+    kat::kat_cfg!(tests / data / data_inherit);
+
+    kat::global! {
+        key_len: usize
+    }
+
+    kat::test! {
+        #[inherit]
+        key_len: usize,
+        #[inherit]
+        mode: String,
+        expected: String,
+    }
+
+    kat::run! {
+        |global, test| -> {
+            assert_eq!(global.key_len, 256);
+            assert_eq!(format!("{}-{}", test.mode, test.key_len), test.expected);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+mod grouped {
+    kat::kat_cfg!(tests / data / data_inherit.json);
+
+    kat::global! {}
+
+    kat::group! {
+        mode: String
+    }
+
+    kat::test! {
+        #[inherit]
+        key_len: usize,
+        expected: String,
+    }
+
+    kat::run! {
+        |_global, group, test| -> {
+            assert_eq!(format!("{}-{}", group.mode, test.key_len), test.expected);
+        }
+    }
+}
+
+mod missing {
+    kat::kat_cfg!(tests / data / data_inherit);
+
+    kat::global! {}
+
+    kat::test! {
+        #[inherit]
+        #[allow(dead_code)]
+        iv_len: usize,
+    }
+
+    kat::run! {
+        #[should_panic(expected = "missing field `iv_len`")]
+        |_global, _test| -> {}
+    }
+}
+
+#[cfg(feature = "json")]
+mod null {
+    kat::kat_cfg!(tests / data / data_inherit_null.json);
+
+    kat::global! {}
+
+    kat::test! {
+        #[inherit]
+        iv: Option<String>,
+        #[inherit]
+        key_len: usize,
+        expected: String,
+    }
+
+    kat::run! {
+        |_global, test| -> {
+            assert_eq!(format!("{}-{:?}", test.key_len, test.iv), test.expected);
+        }
+    }
+}
+
+mod rename {
+    kat::kat_cfg!(tests / data / data_inherit_rename);
+
+    kat::global! {}
+
+    // `#[inherit]` may be put in front of, or after the other attributes
+    kat::test! {
+        #![serde(rename_all = "camelCase")]
+        #[inherit]
+        key_len: usize,
+        #[serde(rename = "cipher-mode")]
+        #[inherit]
+        mode: String,
+        expected: String,
+    }
+
+    kat::run! {
+        |_global, test| -> {
+            assert_eq!(format!("{}-{}", test.mode, test.key_len), test.expected);
+        }
+    }
+}
+
+mod kinds {
+    kat::kat_cfg!(tests / data / data_inherit_kinds);
+
+    kat::global! {}
+
+    // Only the `[[encrypt]]` cases inherit the mode
+    kat::test! {
+        encrypt: Encrypt {
+            #[inherit]
+            mode: String,
+            plaintext: String,
+            expected: String,
+        },
+        decrypt: Decrypt {
+            mode: Option<String>,
+            ciphertext: String,
+            expected: String,
+        },
+    }
+
+    kat::run! {
+        encrypt: |_global, test| -> {
+            assert_eq!(format!("{}:{}", test.mode, test.plaintext), test.expected);
+        },
+        decrypt: |_global, test| -> {
+            assert_eq!(format!("{:?}:{}", test.mode, test.ciphertext), test.expected);
+        },
+    }
+}
@@ -0,0 +1,67 @@
+kat::kat_suite!(data {
+    cfg: tests / data / data,
+    global: {
+        name: String,
+        value: usize
+    },
+    test: {
+        test_name: String,
+        value: usize
+    },
+    run: |global, test| {
+        assert_eq!(global.name, "GLOBAL");
+        assert_eq!(global.value, 69);
+        assert_eq!(test.test_name, "TEST");
+        assert_eq!(test.value, 420);
+    }
+});
+
+kat::kat_suite!(multiply {
+    cfg: tests / data / data_suite.toml,
+    global: {
+        factor: i64
+    },
+    test: {
+        input: i64,
+        expected: i64
+    },
+    run: |global, test| {
+        assert_eq!(test.input * global.factor, test.expected);
+    },
+});
+
+kat::kat_suite!(multiply_wrong {
+    cfg: tests / data / data_suite,
+    global: {
+        factor: i64
+    },
+    test: {
+        #![derive(Debug)]
+        input: i64,
+        expected: i64
+    },
+    run: #[should_panic(expected = "3 of 3 test cases failed:")] |global, test| -> {
+        assert_eq!(test.input * global.factor, test.expected + 1);
+    }
+});
+
+#[cfg(feature = "wycheproof")]
+kat::kat_suite!(wycheproof {
+    cfg: tests / data / data_wycheproof.json, format = Wycheproof,
+    global: {
+        algorithm: String
+    },
+    group: {
+        #![serde(rename_all = "camelCase")]
+        key_size: usize
+    },
+    test: {
+        #![serde(rename_all = "camelCase")]
+        tc_id: usize
+    },
+    run: |global, group, test| {
+        assert_eq!(global.algorithm, "AES-GCM");
+        assert!(group.key_size > 0);
+        assert!(test.tc_id > 0);
+    }
+});
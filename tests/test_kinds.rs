@@ -0,0 +1,187 @@
+fn shift(text: &str, shift: i8) -> String {
+    text.bytes().map(|b| (b as i8 + shift) as u8 as char).collect()
+}
+
+macro_rules! caesar_test {
+    ($($cfg: tt)*) => {
+        use super::shift;
+
+        kat::kat_cfg!($($cfg)*);
+
+        kat::global! {
+            shift: i8
+        }
+
+        kat::test! {
+            encrypt: Encrypt {
+                plaintext: String,
+                ciphertext: String,
+            },
+            decrypt: Decrypt {
+                ciphertext: String,
+                plaintext: String,
+            },
+        }
+
+        kat::run! {
+            encrypt: |global, test| -> {
+                assert_eq!(shift(&test.plaintext, global.shift), test.ciphertext);
+            },
+            decrypt: |global, test| -> {
+                assert_eq!(shift(&test.ciphertext, -global.shift), test.plaintext);
+            }
+        }
+    };
+}
+
+mod toml {
+    caesar_test!(tests / data / data_kinds);
+}
+
+#[cfg(feature = "ron")]
+mod ron {
+    caesar_test!(tests / data / data_kinds.ron);
+}
+
+mod fallible {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    kat::kat_cfg!(tests / data / data_kinds);
+
+    kat::global! {}
+
+    kat::test! {
+        encrypt: Encrypt {
+            #![allow(dead_code)]
+            plaintext: String,
+        },
+        decrypt: Decrypt {
+            #![allow(dead_code)]
+            ciphertext: String,
+        },
+    }
+
+    kat::run! {
+        setup: || -> { RUNS.store(0, Ordering::SeqCst) },
+        teardown: |_fixture| -> { assert_eq!(RUNS.load(Ordering::SeqCst), 3) },
+        parallel encrypt: |_global, _test, _fixture| -> Result<(), String> {
+            RUNS.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        },
+        decrypt: |_global, _test, _fixture| -> {
+            RUNS.fetch_add(1, Ordering::SeqCst);
+        },
+    }
+}
+
+mod failing {
+    kat::kat_cfg!(tests / data / data_kinds);
+
+    kat::global! {}
+
+    kat::test! {
+        encrypt: Encrypt {
+            id: usize,
+        },
+        decrypt: Decrypt {
+            id: usize,
+        },
+    }
+
+    // Cases are numbered in file order, whatever their kind
+    kat::run! {
+        #[should_panic(expected = "data_kinds.toml:9-12): decrypt 1\n  case 2 (id = 2, ")]
+        encrypt: |_global, test| -> {
+            assert!(test.id == 0, "encrypt {}", test.id);
+        },
+        decrypt: |_global, test| -> {
+            panic!("decrypt {}", test.id);
+        },
+    }
+}
+
+mod file_order {
+    use std::sync::Mutex;
+
+    static IDS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+    kat::kat_cfg!(tests / data / data_kinds);
+
+    kat::global! {}
+
+    kat::test! {
+        encrypt: Encrypt {
+            id: usize,
+        },
+        decrypt: Decrypt {
+            id: usize,
+        },
+    }
+
+    kat::run! {
+        setup: || -> { IDS.lock().unwrap().clear() },
+        teardown: |_fixture| -> { assert_eq!(*IDS.lock().unwrap(), [0, 1, 2]) },
+        encrypt: |_global, test, _fixture| -> {
+            IDS.lock().unwrap().push(test.id);
+        },
+        decrypt: |_global, test, _fixture| -> {
+            IDS.lock().unwrap().push(test.id);
+        },
+    }
+}
+
+mod sections {
+    use super::shift;
+
+    kat::kat_cfg!(tests / data / data_kinds_sections);
+
+    kat::global! {
+        shift: i8
+    }
+
+    kat::test! {
+        encrypt = "shift-encrypt": Encrypt {
+            plaintext: String,
+            ciphertext: String,
+        },
+        decrypt = "shift-decrypt": Decrypt {
+            ciphertext: String,
+            plaintext: String,
+        },
+    }
+
+    kat::run! {
+        #[should_panic(expected = "data_kinds_sections.toml:9-11): assertion")]
+        encrypt: |global, test| -> {
+            assert_eq!(shift(&test.plaintext, global.shift), test.ciphertext);
+        },
+        decrypt: |global, test| -> {
+            assert_eq!(shift(&test.ciphertext, -global.shift), test.plaintext);
+        }
+    }
+}
+
+mod groups {
+    kat::kat_cfg!(tests / data / data_kinds_groups);
+
+    kat::global! {}
+
+    kat::test! {
+        encrypt: Encrypt {
+            #![allow(dead_code)]
+            plaintext: String,
+        },
+        decrypt: Decrypt {
+            #![allow(dead_code)]
+            ciphertext: String,
+        },
+    }
+
+    kat::run! {
+        #[should_panic(expected = "several kinds of test cases can't be inside of groups")]
+        encrypt: |_global, _test| -> {},
+        decrypt: |_global, _test| -> {},
+    }
+}
@@ -0,0 +1,65 @@
+macro_rules! multiply_test {
+    ($($cfg: tt)*) => {
+        kat::kat_cfg!($($cfg)*);
+
+        kat::global! {
+            factor: i64
+        }
+
+        kat::test! {
+            input: i64,
+            expected: i64
+        }
+
+        kat::run! {
+            |global, test| -> {
+                assert_eq!(test.input * global.factor, test.expected);
+            }
+        }
+    };
+}
+
+mod directory {
+    multiply_test!(tests / data / multi);
+}
+
+mod glob {
+    multiply_test!(tests / data / multi / *.toml);
+}
+
+mod literal_glob {
+    multiply_test!("tests/data/multi/vectors_?.toml");
+}
+
+#[cfg(feature = "json")]
+mod failing {
+    kat::kat_cfg!(tests / data / multi / *);
+
+    kat::global! {
+        factor: i64
+    }
+
+    kat::test! {
+        input: i64
+    }
+
+    kat::run! {
+        #[should_panic(expected = "2 of 4 test cases in 2 of 3 files failed:")]
+        |global, test| -> {
+            assert!(test.input * global.factor < 10);
+        }
+    }
+}
+
+mod no_match {
+    kat::kat_cfg!(tests / data / multi / *.ron);
+
+    kat::global! {}
+
+    kat::test! {}
+
+    kat::run! {
+        #[should_panic(expected = "multi/*.ron is not a file, or wasn't found")]
+        |_global, _test| -> {}
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+
+use kat::Expectation;
+
+#[derive(Debug, PartialEq)]
+enum DecryptError {
+    InvalidTag,
+    InvalidPadding,
+}
+
+impl fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecryptError::InvalidTag => f.write_str("invalid tag"),
+            DecryptError::InvalidPadding => f.write_str("invalid padding"),
+        }
+    }
+}
+
+fn decrypt(input: &str) -> Result<usize, DecryptError> {
+    match input {
+        "bad tag" => Err(DecryptError::InvalidTag),
+        "bad padding" => Err(DecryptError::InvalidPadding),
+        _ => Ok(input.len()),
+    }
+}
+
+mod passing {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_outcome);
+
+    kat::global! {}
+
+    kat::test! {
+        input: String
+    }
+
+    kat::run! {
+        |_global, test| -> {
+            kat::check_outcome_with(test, &decrypt(&test.input), |err| format!("{:?}", err));
+        }
+    }
+}
+
+mod failing {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_outcome_failing);
+
+    kat::global! {}
+
+    kat::test! {
+        input: String
+    }
+
+    kat::run! {
+        #[should_panic(expected = "3 of 3 test cases failed:")]
+        |_global, test| -> {
+            kat::check_outcome_with(test, &decrypt(&test.input), |err| format!("{:?}", err));
+        }
+    }
+}
+
+mod cavp {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_outcome.rsp);
+
+    kat::global! {}
+
+    kat::test! {
+        signature: String
+    }
+
+    kat::run! {
+        |_global, test| -> {
+            kat::check_outcome(test, &decrypt(&test.signature));
+        }
+    }
+}
+
+// The worker threads of parallel runners know the outcome of their cases as well
+mod parallel {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_outcome);
+
+    kat::global! {}
+
+    kat::test! {
+        input: String
+    }
+
+    kat::run! {
+        parallel |_global, test| -> {
+            kat::check_outcome_with(test, &decrypt(&test.input), |err| format!("{:?}", err));
+        }
+    }
+}
+
+// Copies of the running case have its expected outcome
+mod copied {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_outcome);
+
+    kat::global! {}
+
+    kat::test! {
+        #![derive(Clone)]
+
+        input: String
+    }
+
+    kat::run! {
+        |_global, test| -> {
+            let copies = [test.clone()];
+            kat::check_outcome_with(&copies[0], &decrypt(&copies[0].input), |err| format!("{:?}", err));
+        }
+    }
+}
+
+#[derive(kat::DeriveTable)]
+struct SuiteGlobal {}
+
+#[derive(kat::DeriveTable)]
+struct SuiteTest {
+    input: String,
+    #[serde(flatten)]
+    outcome: Expectation,
+}
+
+#[test]
+fn check_suite_cases() {
+    let suite = kat::Suite::<SuiteGlobal, SuiteTest>::load("tests/data/data_outcome").unwrap();
+    for test in suite.cases() {
+        kat::check_outcome_with(&test.outcome, &decrypt(&test.input), |err| format!("{:?}", err));
+    }
+}
+
+fn check_message(expectation: Expectation, input: &str) -> String {
+    let payload = std::panic::catch_unwind(|| expectation.check(&decrypt(input))).unwrap_err();
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => payload.downcast_ref::<String>().unwrap().clone(),
+    }
+}
+
+#[test]
+fn check_expectation() {
+    let valid = Expectation::default();
+    let invalid = Expectation {
+        valid: Some(false),
+        ..Default::default()
+    };
+    let invalid_tag = Expectation {
+        expected_error: Some("invalid tag".to_string()),
+        ..Default::default()
+    };
+
+    valid.check(&decrypt("ok"));
+    invalid.check(&decrypt("bad tag"));
+    invalid_tag.check(&decrypt("bad tag"));
+
+    assert_eq!(check_message(valid, "bad tag"), "expected Ok, but returned Err: invalid tag");
+    assert_eq!(check_message(invalid, "ok"), "expected an error, but returned Ok");
+    assert_eq!(
+        check_message(invalid_tag.clone(), "ok"),
+        "expected the error `invalid tag`, but returned Ok"
+    );
+    assert_eq!(
+        check_message(invalid_tag, "bad padding"),
+        "expected the error `invalid tag`, but returned Err `invalid padding`: invalid padding"
+    );
+
+    let contradiction = Expectation {
+        valid: Some(true),
+        expected_error: Some("invalid tag".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        check_message(contradiction, "ok"),
+        "test case is valid, but expects the error `invalid tag`"
+    );
+
+    let acceptable = Expectation {
+        acceptable: true,
+        ..Default::default()
+    };
+    acceptable.check(&decrypt("ok"));
+    acceptable.check(&decrypt("bad tag"));
+}
@@ -0,0 +1,26 @@
+mod parallel_run {
+    kat::kat_cfg!(tests / data / data_sample);
+
+    kat::global! {}
+
+    kat::test! {
+        id: usize
+    }
+
+    kat::run! {
+        parallel |_global, test| -> {
+            assert!(test.id < 12);
+        }
+    }
+}
+
+kat::kat_suite!(parallel_suite {
+    cfg: tests / data / data_sample,
+    global: {},
+    test: {
+        id: usize
+    },
+    run: parallel |_global, test| {
+        assert!(test.id < 12);
+    },
+});
@@ -0,0 +1,96 @@
+mod common;
+
+use std::path::Path;
+
+kat::kat_cfg!(tests / data / data_report);
+
+kat::global! {}
+
+kat::test! {
+    ok: bool
+}
+
+// Run by `write_reports` with the report configured
+kat::run! {
+    #[ignore]
+    |_global, test| -> {
+        assert!(test.ok, "not ok");
+    }
+}
+
+/// Runs the suite, which has a failing case, and returns the written report
+fn report(kind: &str, extension: &str) -> String {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("kat_report");
+    let filepath = dir.join(format!("test_report.{}", extension));
+    let _ = std::fs::remove_file(&filepath);
+
+    let report = format!("{}:{}", kind, dir.display());
+    let message = common::with_env(&[("KAT_REPORT", &report)], kat_tests::tests).unwrap_err();
+    assert!(message.starts_with("1 of 5 test cases failed"), "{}", message);
+
+    std::fs::read_to_string(filepath)
+        .unwrap()
+        .lines()
+        // Durations differ on every run
+        .map(|line| match line.find("time=\"") {
+            Some(start) => {
+                let end = start + 6 + line[start + 6..].find('"').unwrap();
+                format!("{}time=\"T{}", &line[..start], &line[end..])
+            }
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// A single test, since the report is configured by the environment
+#[test]
+fn write_reports() {
+    let file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/data_report.toml");
+    let file = file.display();
+
+    assert_eq!(
+        report("junit-dir", "xml"),
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="test_report" tests="5" failures="1" errors="0" skipped="1" time="T">
+    <testcase name="id = 1" classname="test_report" file="{file}" time="T"/>
+    <testcase name="id = 2" classname="test_report" file="{file}" time="T">
+      <failure message="not ok">not ok</failure>
+    </testcase>
+    <testcase name="id = 3" classname="test_report" file="{file}" time="T">
+      <skipped message="too slow"/>
+    </testcase>
+    <testcase name="id = 4" classname="test_report" file="{file}" time="T">
+      <system-out>failed as expected: not ok</system-out>
+    </testcase>
+    <testcase name="name = &quot;&lt;escaped&gt; &amp; \&quot;quoted\&quot;&quot;" classname="test_report" file="{file}" time="T"/>
+  </testsuite>
+</testsuites>"#
+        )
+    );
+
+    let json = report("json-dir", "json");
+    let json = json.lines().collect::<Vec<_>>();
+    assert_eq!(json[1], r#"  "suite": "test_report","#);
+    assert!(json[2].starts_with(r#"  "duration": "#), "{}", json[2]);
+    assert_eq!(json[3], r#"  "cases": ["#);
+    assert!(json[4].starts_with(&format!(
+        r#"    {{"file": "{}", "index": 0, "id": "1", "name": null, "outcome": "passed", "duration": "#,
+        file
+    )));
+    assert!(json[5].contains(r#""index": 1, "id": "2", "name": null, "outcome": "failed""#));
+    assert!(json[5].ends_with(r#""message": "not ok"},"#), "{}", json[5]);
+    assert!(json[6].contains(r#""outcome": "skipped""#));
+    assert!(json[6].ends_with(r#""message": "too slow"},"#), "{}", json[6]);
+    assert!(json[7].contains(r#""outcome": "expected_failure""#));
+    assert!(json[8].contains(r#""id": null, "name": "<escaped> & \"quoted\"", "outcome": "passed""#));
+    assert!(json[8].ends_with(r#""message": null}"#), "{}", json[8]);
+    assert_eq!(json[9..], ["  ]", "}"]);
+
+    assert_eq!(
+        common::with_env(&[("KAT_REPORT", "xml")], kat_tests::tests).unwrap_err(),
+        "Invalid KAT_REPORT: `xml` is no report like `junit-dir:path` or `json-dir:path`"
+    );
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+
+#[derive(Debug)]
+struct MismatchError(u32, u32);
+
+impl fmt::Display for MismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, got {}", self.0, self.1)
+    }
+}
+
+mod passing {
+    kat::kat_cfg!(tests / data / data_sample);
+
+    kat::global! {}
+
+    kat::test! {
+        id: usize
+    }
+
+    kat::run! {
+        |_global, test| -> Result<(), std::num::ParseIntError> {
+            let id: usize = test.id.to_string().parse()?;
+            assert_eq!(id, test.id);
+            Ok(())
+        }
+    }
+}
+
+mod failing {
+    kat::kat_cfg!(tests / data / data_result);
+
+    kat::global! {
+        base: u32
+    }
+
+    kat::test! {
+        input: String,
+        expected: u32
+    }
+
+    kat::run! {
+        #[should_panic(expected = "case 1 (id = 2, ")]
+        |global, test| -> Result<(), String> {
+            let value = u32::from_str_radix(&test.input, global.base).map_err(|err| err.to_string())?;
+            if value != test.expected {
+                return Err(format!("{} is not {}", value, test.expected));
+            }
+            Ok(())
+        }
+    }
+}
+
+kat::kat_suite!(custom_error {
+    cfg: tests / data / data_result,
+    global: {
+        base: u32
+    },
+    test: {
+        input: String,
+        expected: u32
+    },
+    run: #[should_panic(expected = "returned Err: expected 16, got 0")] |global, test| -> Result<(), MismatchError> {
+        let value = u32::from_str_radix(&test.input, global.base).unwrap_or_default();
+        match value == test.expected {
+            true => Ok(()),
+            false => Err(MismatchError(test.expected, value)),
+        }
+    },
+});
+
+#[test]
+fn check_result() {
+    kat::__check_result::<String>(Ok(()));
+
+    let failed = std::panic::catch_unwind(|| kat::__check_result(Err("invalid input"))).unwrap_err();
+    assert_eq!(failed.downcast_ref::<String>().unwrap(), "returned Err: invalid input");
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Ids of the cases, which were run
+static RUN: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+/// Whether the cases fail
+static FAIL: AtomicBool = AtomicBool::new(false);
+
+kat::kat_cfg!(tests / data / data_sample);
+
+kat::global! {}
+
+kat::test! {
+    id: usize
+}
+
+// Run by `shard_and_sample_cases` with the selection set
+kat::run! {
+    #[ignore]
+    |_global, test| -> {
+        RUN.lock().unwrap().push(test.id);
+        assert!(!FAIL.load(Ordering::SeqCst), "failed");
+    }
+}
+
+/// Runs the suite with the environment variables set, returns the ids of the cases, which were run
+fn run(vars: &[(&str, &str)], fail: bool) -> Result<Vec<usize>, String> {
+    RUN.lock().unwrap().clear();
+    FAIL.store(fail, Ordering::SeqCst);
+    common::with_env(vars, kat_tests::tests)?;
+    Ok(std::mem::take(&mut *RUN.lock().unwrap()))
+}
+
+// A single test, since the selection is read from the environment
+#[test]
+fn shard_and_sample_cases() {
+    let selected = |vars: &[(&str, &str)]| run(vars, false).unwrap();
+
+    assert_eq!(selected(&[("KAT_SHARD", "1/3")]), [0, 3, 6, 9]);
+    assert_eq!(selected(&[("KAT_SHARD", "2/3")]), [1, 4, 7, 10]);
+    assert_eq!(selected(&[("KAT_SHARD", "3/3")]), [2, 5, 8, 11]);
+    assert_eq!(selected(&[("KAT_SHARD", "1/1")]), (0..12).collect::<Vec<_>>());
+
+    // Shards split the filtered cases
+    assert_eq!(selected(&[("KAT_FILTER", "6.."), ("KAT_SHARD", "2/2")]), [7, 9, 11]);
+
+    let sample = selected(&[("KAT_SAMPLE", "5"), ("KAT_SEED", "7")]);
+    assert_eq!(sample.len(), 5);
+    assert!(sample.windows(2).all(|ids| ids[0] < ids[1]), "{:?}", sample);
+    assert_eq!(selected(&[("KAT_SAMPLE", "5"), ("KAT_SEED", "7")]), sample);
+
+    assert_eq!(selected(&[("KAT_SAMPLE", "25%"), ("KAT_SEED", "7")]).len(), 3);
+    assert_eq!(selected(&[("KAT_SAMPLE", "1%")]).len(), 1);
+    assert_eq!(selected(&[("KAT_SAMPLE", "100")]), (0..12).collect::<Vec<_>>());
+
+    // Samples are taken of a shard
+    let shard = selected(&[("KAT_SHARD", "1/2"), ("KAT_SAMPLE", "50%"), ("KAT_SEED", "3")]);
+    assert_eq!(shard.len(), 3);
+    assert!(shard.iter().all(|id| id % 2 == 0), "{:?}", shard);
+
+    let message = run(&[("KAT_SAMPLE", "2"), ("KAT_SEED", "7")], true).unwrap_err();
+    assert!(
+        message.starts_with("2 of 2 test cases failed (10 filtered out, sampled with KAT_SEED=7):"),
+        "{}",
+        message
+    );
+
+    assert_eq!(
+        run(&[("KAT_SHARD", "0/3")], false).unwrap_err(),
+        "Invalid KAT_SHARD: `0/3` is no shard like `1/4`, counting from 1"
+    );
+    assert_eq!(
+        run(&[("KAT_SAMPLE", "half")], false).unwrap_err(),
+        "Invalid KAT_SAMPLE: `half` is no percentage like `10%`, or count like `500`"
+    );
+}
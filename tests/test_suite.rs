@@ -0,0 +1,30 @@
+use kat::DeriveTable;
+
+#[derive(DeriveTable)]
+struct Global {
+    factor: i64,
+}
+
+#[derive(DeriveTable)]
+struct Test {
+    input: i64,
+    expected: i64,
+}
+
+#[kat::suite("tests/data/data_suite.toml")]
+fn suite(global: &Global, test: &Test) {
+    assert_eq!(test.input * global.factor, test.expected);
+}
+
+#[kat::suite("tests/data/data_suite")]
+#[should_panic(expected = "data_suite.toml:")]
+fn failing_suite(global: &Global, test: &Test) {
+    assert_eq!(test.input * global.factor, test.expected + 1);
+}
+
+
+// Cases, whose names collide with the suffixed name of an earlier case
+#[kat::suite("tests/data/data_suite_names")]
+fn colliding_names(global: &Global, test: &Test) {
+    assert_eq!(test.input * global.factor, test.expected);
+}
@@ -0,0 +1,139 @@
+use kat::{DeriveTable, Format, KatError, Suite};
+
+#[derive(DeriveTable)]
+struct Global {
+    name: String,
+    value: usize,
+}
+
+#[derive(DeriveTable)]
+struct Test {
+    test_name: String,
+    value: usize,
+}
+
+#[test]
+fn load() {
+    let suite = Suite::<Global, Test>::load("tests/data/data").unwrap();
+
+    assert_eq!(suite.format(), Format::Toml);
+    assert!(suite.path().ends_with("tests/data/data.toml"));
+    assert_eq!(suite.len(), 1);
+
+    let global = suite.global().unwrap();
+    assert_eq!(global.name, "GLOBAL");
+    assert_eq!(global.value, 69);
+
+    let test = suite.cases().next().unwrap();
+    assert_eq!(test.test_name, "TEST");
+    assert_eq!(test.value, 420);
+}
+
+#[derive(DeriveTable)]
+struct CavpGlobal {
+    keylen: usize,
+}
+
+#[derive(DeriveTable)]
+struct CavpTest {
+    count: usize,
+}
+
+#[test]
+fn load_sections() {
+    let suite = Suite::<CavpGlobal, CavpTest>::load("tests/data/data_cavp_params.rsp").unwrap();
+
+    assert_eq!(suite.format(), Format::Cavp);
+    for (global, _, test) in suite.iter() {
+        assert!(global.keylen > 0);
+        assert!(test.count < suite.len());
+    }
+}
+
+#[test]
+fn load_missing_file() {
+    let err = Suite::<Global, Test>::load("tests/data/missing.toml").err().unwrap();
+    assert!(err.to_string().ends_with("missing.toml is not a file, or wasn't found"), "{}", err);
+    assert!(matches!(err, KatError::NotFound { path } if path.ends_with("tests/data/missing.toml")));
+}
+
+#[derive(DeriveTable)]
+struct Value {
+    #[allow(dead_code)]
+    value: usize,
+}
+
+#[test]
+fn parse_error() {
+    let err = Suite::<Value, Value>::load("tests/data/data_parse_error").err().unwrap();
+    assert!(err.to_string().contains("data_parse_error.toml:8:9: "), "{}", err);
+    assert!(err.to_string().ends_with(", at test[1].value"), "{}", err);
+
+    match err {
+        KatError::Parse {
+            format,
+            line,
+            column,
+            field,
+            ..
+        } => {
+            assert_eq!(format, Format::Toml);
+            assert_eq!(line, Some(8));
+            assert_eq!(column, Some(9));
+            assert_eq!(field.as_deref(), Some("test[1].value"));
+        }
+        err => panic!("expected a parse error, found {:?}", err),
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn parse_error_json() {
+    let err = Suite::<Value, Value>::load("tests/data/data_parse_error.json").err().unwrap();
+    assert!(err.to_string().starts_with("Unable to parse json file "), "{}", err);
+    assert!(err.to_string().contains("data_parse_error.json:5:26: "), "{}", err);
+    assert!(err.to_string().ends_with(", at test[1].value"), "{}", err);
+}
+
+#[cfg(feature = "ron")]
+#[test]
+fn parse_error_ron() {
+    let err = Suite::<Value, Value>::load("tests/data/data_parse_error.ron").err().unwrap();
+    assert!(err.to_string().starts_with("Unable to parse ron file "), "{}", err);
+    assert!(err.to_string().contains("data_parse_error.ron:5:"), "{}", err);
+    assert!(err.to_string().ends_with(", at test[1].value"), "{}", err);
+}
+
+#[test]
+fn reserved_key_error() {
+    let err = Suite::<Value, Value>::load("tests/data/data_meta_error").err().unwrap();
+    assert!(err.to_string().contains("data_meta_error.toml:"), "{}", err);
+    assert!(err.to_string().contains("expected a tag, or a list of tags"), "{}", err);
+    assert!(matches!(err, KatError::Parse { field: Some(field), .. } if field == "test[1].tags"));
+
+    let err = Suite::<Value, Value>::load("tests/data/data_meta_skip_error").err().unwrap();
+    assert!(err.to_string().contains("expected a flag, or a string with the reason"), "{}", err);
+    assert!(err.to_string().ends_with(", at test[0].skip"), "{}", err);
+}
+
+#[test]
+fn parse_error_cavp() {
+    let err = Suite::<CavpGlobal, Value>::load("tests/data/data_cavp_params.rsp").err().unwrap();
+
+    match err {
+        KatError::Parse { line, field, .. } => {
+            assert_eq!(line, Some(8));
+            assert_eq!(field, None);
+        }
+        err => panic!("expected a parse error, found {:?}", err),
+    }
+}
+
+#[test]
+fn validation_error() {
+    let err = Suite::<Global, Test>::load("tests/data/data.txt").err().unwrap();
+    assert!(matches!(err, KatError::Validation { .. }), "{:?}", err);
+
+    let err = Suite::<Global, Test, Value>::load_grouped("tests/data/data_cavp.rsp", Format::Cavp).err().unwrap();
+    assert_eq!(err.to_string(), "cavp files don't support test groups");
+}
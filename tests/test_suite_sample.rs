@@ -0,0 +1,24 @@
+mod common;
+
+use kat::DeriveTable;
+
+#[derive(DeriveTable)]
+struct Global {}
+
+#[derive(DeriveTable)]
+struct Test {
+    id: usize,
+}
+
+// Run by `failure_prints_seed` with the sample set
+#[kat::suite("tests/data/data_sample.toml")]
+#[ignore]
+fn sampled(_global: &Global, test: &Test) {
+    panic!("case {} failed", test.id);
+}
+
+#[test]
+fn failure_prints_seed() {
+    let message = common::with_env(&[("KAT_SAMPLE", "100%"), ("KAT_SEED", "7")], sampled::id_0).unwrap_err();
+    assert!(message.ends_with("sampled with KAT_SEED=7"), "{}", message);
+}
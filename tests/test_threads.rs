@@ -0,0 +1,78 @@
+mod common;
+
+use std::sync::{Barrier, Mutex};
+
+/// Ids of the cases, which were run
+static RUN: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+kat::kat_cfg!(tests / data / data_sample);
+
+kat::global! {}
+
+kat::test! {
+    #![derive(Debug)]
+
+    id: usize
+}
+
+// Every case waits for three others, so the run only finishes,
+// if four cases run at the same time
+mod together {
+    use super::*;
+
+    static BARRIER: Barrier = Barrier::new(4);
+
+    kat::run! {
+        #[ignore]
+        parallel |_global, test| -> {
+            BARRIER.wait();
+            RUN.lock().unwrap().push(test.id);
+        }
+    }
+
+    pub fn run(vars: &[(&str, &str)]) -> Result<(), String> {
+        common::with_env(vars, kat_tests::tests)
+    }
+}
+
+mod failing {
+    use super::*;
+
+    kat::run! {
+        #[ignore]
+        parallel |_global, test| -> {
+            assert!(test.id % 4 != 1, "case {} failed", test.id);
+        }
+    }
+
+    pub fn run(vars: &[(&str, &str)]) -> Result<(), String> {
+        common::with_env(vars, kat_tests::tests)
+    }
+}
+
+// The only test of its binary, since other parallel suites would read KAT_THREADS as well
+#[test]
+fn run_across_threads() {
+    let threads = [("KAT_THREADS", "4")];
+
+    together::run(&threads).unwrap();
+    let mut run = RUN.lock().unwrap().clone();
+    run.sort();
+    assert_eq!(run, (0..12).collect::<Vec<_>>());
+
+    // Failures are reported in the order of the file
+    let message = failing::run(&threads).unwrap_err();
+
+    let lines: Vec<&str> = message.lines().collect();
+    assert_eq!(lines[0], "3 of 12 test cases failed:");
+    for (line, id) in lines[1..].chunks(2).zip([1, 5, 9]) {
+        assert!(line[0].starts_with(&format!("  case {} (", id)), "{}", message);
+        assert!(line[0].ends_with(&format!("case {} failed", id)), "{}", message);
+        assert_eq!(line[1], format!("    test case: __KatTest {{ id: {} }}", id));
+    }
+
+    assert_eq!(
+        failing::run(&[("KAT_THREADS", "none")]).unwrap_err(),
+        "Invalid KAT_THREADS: `none` is no positive integer"
+    );
+}
@@ -0,0 +1,80 @@
+use kat::{impl_deserialize_try_from_deserializable, impl_deserialize_try_from_toml_int};
+
+struct Length(usize);
+
+impl_deserialize_try_from_toml_int!(
+    |i| -> Result<Length, String> {
+        usize::try_from(i)
+            .map(Length)
+            .map_err(|_| format!("invalid length {}", i))
+    }
+);
+
+struct Even(Length);
+
+impl_deserialize_try_from_deserializable!(
+    |length: Length| -> Result<Even, &'static str> {
+        if length.0.is_multiple_of(2) {
+            Ok(Even(length))
+        } else {
+            Err("length is odd")
+        }
+    }
+);
+
+mod valid {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_try_from);
+
+    kat::global! {
+        length: Length
+    }
+
+    kat::test! {
+        even: Even
+    }
+
+    kat::run! {
+        |global, test| -> {
+            assert_eq!(global.length.0, 22);
+            assert!(test.even.0.0.is_multiple_of(2));
+        }
+    }
+}
+
+mod negative {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_try_from_negative);
+
+    kat::global! {
+        #[allow(dead_code)]
+        length: Length
+    }
+
+    kat::test! {}
+
+    kat::run! {
+        #[should_panic(expected = "invalid length -1 for key `global.length`")]
+        |_global, _test| -> {}
+    }
+}
+
+mod odd {
+    use super::*;
+
+    kat::kat_cfg!(tests / data / data_try_from_odd);
+
+    kat::global! {}
+
+    kat::test! {
+        #[allow(dead_code)]
+        even: Even
+    }
+
+    kat::run! {
+        #[should_panic(expected = "length is odd for key `test.even`")]
+        |_global, _test| -> {}
+    }
+}
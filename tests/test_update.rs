@@ -0,0 +1,86 @@
+mod common;
+
+use kat::types::TomlHex;
+
+const DATA: &str = r#"# Golden values
+[global]
+prefix = "kat"
+
+[[test]]
+input = "abc"
+expected = "wrong" # stale
+
+[[test]]
+# already up to date
+input = "def"
+expected = "katdef"
+digest = "00"
+
+[[test]]
+input = "ghi"
+"#;
+
+const UPDATED: &str = r#"# Golden values
+[global]
+prefix = "kat"
+
+[[test]]
+input = "abc"
+expected = "katabc" # stale
+digest = "030303"
+
+[[test]]
+# already up to date
+input = "def"
+expected = "katdef"
+digest = "030303"
+
+[[test]]
+input = "ghi"
+expected = "katghi"
+digest = "030303"
+"#;
+
+// kat_cfg paths are relative to the package, the file is rewritten by the test
+kat::kat_cfg!(target / kat_update / data_update);
+
+kat::global! {
+    prefix: String
+}
+
+kat::test! {
+    input: String,
+    expected: Option<String>,
+    digest: Option<TomlHex>
+}
+
+// Run by `update_expected_values`, with and without KAT_UPDATE
+kat::run! {
+    #[ignore]
+    |global, test| -> {
+        let actual = format!("{}{}", global.prefix, test.input);
+        kat::expect!(test.expected, actual);
+        kat::expect!(test.digest, TomlHex::from(vec![test.input.len() as u8; 3]));
+    }
+}
+
+fn run(vars: &[(&str, &str)]) -> Result<(), String> {
+    common::with_env(vars, kat_tests::tests)
+}
+
+#[test]
+fn update_expected_values() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("target/kat_update");
+    let filepath = dir.join("data_update.toml");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&filepath, DATA).unwrap();
+
+    let message = run(&[]).unwrap_err();
+    assert!(message.starts_with("3 of 3 test cases failed:"), "{}", message);
+    assert!(message.contains("`expected` doesn't match"), "{}", message);
+
+    run(&[("KAT_UPDATE", "1")]).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&filepath).unwrap(), UPDATED);
+    run(&[]).unwrap();
+}
@@ -0,0 +1,67 @@
+mod common;
+
+const DATA: &str = r#"[global]
+
+[[encrypt]]
+input = "abc"
+
+[[decrypt]]
+input = "cba"
+
+[[encrypt]]
+input = "def"
+"#;
+
+const UPDATED: &str = r#"[global]
+
+[[encrypt]]
+input = "abc"
+expected = "ABC"
+
+[[decrypt]]
+input = "cba"
+expected = "cba"
+
+[[encrypt]]
+input = "def"
+expected = "DEF"
+"#;
+
+// The cases are updated in file order, whatever their kind
+kat::kat_cfg!(target / kat_update / data_update_kinds);
+
+kat::global! {}
+
+kat::test! {
+    encrypt: Encrypt {
+        input: String,
+        expected: Option<String>,
+    },
+    decrypt: Decrypt {
+        input: String,
+        expected: Option<String>,
+    },
+}
+
+// Run by `update_expected_values_of_several_kinds` with KAT_UPDATE set
+kat::run! {
+    #[ignore]
+    encrypt: |_global, test| -> {
+        kat::expect!(test.expected, test.input.to_uppercase());
+    },
+    decrypt: |_global, test| -> {
+        kat::expect!(test.expected, test.input.to_lowercase());
+    },
+}
+
+#[test]
+fn update_expected_values_of_several_kinds() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("target/kat_update");
+    let filepath = dir.join("data_update_kinds.toml");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&filepath, DATA).unwrap();
+
+    common::with_env(&[("KAT_UPDATE", "1")], kat_tests::tests).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&filepath).unwrap(), UPDATED);
+}
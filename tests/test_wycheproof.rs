@@ -0,0 +1,107 @@
+use kat::wycheproof::TestResult;
+
+kat::kat_cfg!(tests / data / data_wycheproof, format = Wycheproof);
+
+kat::global! {
+    #![serde(rename_all = "camelCase")]
+
+    algorithm: String,
+    number_of_tests: usize
+}
+
+kat::group! {
+    #![serde(rename_all = "camelCase")]
+
+    key_size: usize,
+    tag_size: usize
+}
+
+kat::test! {
+    #![serde(rename_all = "camelCase")]
+
+    tc_id: usize,
+    tag: String,
+    result: TestResult,
+    flags: Vec<String>
+}
+
+// Accepts tags, which match the groups tag size
+fn verify(tag: &str, tag_size: usize) -> Result<(), String> {
+    if tag.len() * 4 == tag_size {
+        Ok(())
+    } else {
+        Err(format!("tag has {} bits", tag.len() * 4))
+    }
+}
+
+kat::run! {
+    |global, group, test| -> {
+        assert_eq!(global.algorithm, "AES-GCM");
+        assert!(test.tc_id <= global.number_of_tests);
+        assert_eq!(group.key_size, 128);
+
+        let result = verify(&test.tag, group.tag_size);
+        test.result.check_result(&result);
+        test.result.check_flagged(result.is_ok(), &test.flags, &["ShortTag"]);
+    }
+}
+
+// The `result` of a case is its expected outcome, acceptable cases may be accepted or rejected
+mod outcome {
+    use super::verify;
+
+    kat::kat_cfg!(tests / data / data_wycheproof, format = Wycheproof);
+
+    kat::global! {}
+
+    kat::group! {
+        #![serde(rename_all = "camelCase")]
+
+        tag_size: usize
+    }
+
+    kat::test! {
+        tag: String
+    }
+
+    kat::run! {
+        |_global, group, test| -> {
+            kat::check_outcome(test, &verify(&test.tag, group.tag_size));
+        }
+    }
+}
+
+mod outcome_failing {
+    kat::kat_cfg!(tests / data / data_wycheproof, format = Wycheproof);
+
+    kat::global! {}
+
+    kat::group! {}
+
+    kat::test! {
+        #![serde(rename_all = "camelCase")]
+
+        tc_id: usize
+    }
+
+    kat::run! {
+        #[should_panic(expected = "2 of 4 test cases failed:")]
+        |_global, _group, test| -> {
+            // Invalid cases have to be rejected
+            assert!(test.tc_id > 0);
+            kat::check_outcome(test, &Ok::<(), String>(()));
+        }
+    }
+}
+
+#[derive(kat::DeriveTable)]
+struct Empty {}
+
+// A case, whose `result` can't be read, fails to load instead of losing its expected outcome
+#[test]
+fn metadata_error() {
+    let path = "tests/data/data_wycheproof_meta_error.json";
+    let err = kat::Suite::<Empty, Empty, Empty>::load_grouped(path, kat::Format::Wycheproof).err().unwrap();
+    assert!(err.to_string().contains("invalid type: integer `1`, expected a string"), "{}", err);
+    assert!(err.to_string().ends_with(", at testGroups[0].tests[0].result"), "{}", err);
+}